@@ -1,14 +1,25 @@
 use rocket::http::Status;
 use rocket::response::status;
+use rocket::response::stream::{Event, EventStream};
 use rocket::serde::json::Json;
+use rocket::tokio::sync::Notify;
+use rocket::tokio::time::sleep;
 use rocket::*;
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, RwLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use transfer::*;
+use xiangqi_core::{pgn_result, GameHistory, GameRecord, GameRules, MoveFormat, PieceColor};
 
 static EXPIRE: std::time::Duration = std::time::Duration::from_secs(20);
+static HEARTBEAT: std::time::Duration = std::time::Duration::from_secs(5);
+/// A player silent for this long while the opponent is still around loses on time.
+static TIMEOUT: std::time::Duration = std::time::Duration::from_secs(15);
+
+fn now() -> Duration {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap()
+}
 
 #[derive(Debug, Clone, Copy)]
 enum RoomStatus {
@@ -25,13 +36,22 @@ impl Default for RoomStatus {
 #[derive(Default)]
 struct Room {
     next: RoomStatus,
-    left: VecDeque<String>,
-    right: VecDeque<String>,
+    history: GameHistory,
+    outcome: Option<GameOutcome>,
+    /// The player whose draw offer is standing, if any.
+    draw_offer: Option<bool>,
+    left: VecDeque<RoomUpdate>,
+    right: VecDeque<RoomUpdate>,
     last: Duration,
+    /// When each player, indexed like the queues, was last heard from.
+    seen: [Duration; 2],
+    /// The token of each seat taken so far, indexed like the queues.
+    tokens: [Option<Token>; 2],
+    notify: Arc<Notify>,
 }
 
 impl Room {
-    fn get(&mut self, player: bool) -> &mut VecDeque<String> {
+    fn get(&mut self, player: bool) -> &mut VecDeque<RoomUpdate> {
         if player {
             &mut self.right
         } else {
@@ -39,14 +59,95 @@ impl Room {
         }
     }
 
+    fn push(&mut self, player: bool, update: RoomUpdate) {
+        self.get(player).push_back(update);
+        self.notify.notify_waiters();
+    }
+
     fn update(&mut self) {
-        self.last = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        self.last = now();
+    }
+
+    /// Gives `player` its seat, returning the token that proves it from now on.
+    fn sit(&mut self, player: bool) -> Token {
+        let token = rand::random();
+        self.tokens[player as usize] = Some(token);
+        self.seen(player);
+        token
+    }
+
+    /// The player holding `token`, who is heard from by sending it.
+    fn player(&mut self, token: Token) -> Result<bool, PlayError> {
+        let seat = self.tokens.iter().position(|own| *own == Some(token));
+        let player = seat.ok_or(PlayError::BadToken)? == 1;
+        self.seen(player);
+        Ok(player)
+    }
+
+    fn seen(&mut self, player: bool) {
+        self.update();
+        self.seen[player as usize] = self.last;
+    }
+
+    /// Ends the game, telling both players why.
+    fn finish(&mut self, outcome: GameOutcome) {
+        self.outcome = Some(outcome);
+        self.draw_offer = None;
+        for player in [false, true] {
+            self.push(player, RoomUpdate::Outcome(outcome));
+        }
+    }
+
+    /// The player that has gone silent while the other one has not.
+    fn timed_out(&self) -> Option<bool> {
+        if !matches!(self.next, RoomStatus::Full) || self.outcome.is_some() {
+            return None;
+        }
+        let now = now();
+        let silent = self.seen.map(|seen| now.saturating_sub(seen) > TIMEOUT);
+        match silent {
+            [true, false] => Some(false),
+            [false, true] => Some(true),
+            _ => None,
+        }
+    }
+
+    fn ply(&self) -> Ply {
+        self.history.len() as Ply
+    }
+
+    fn resync(&self) -> Resync {
+        Resync {
+            ply: self.ply(),
+            board: self.history.board().into(),
+            outcome: self.outcome,
+        }
+    }
+
+    fn play(&mut self, player: bool, mv: &Move) -> Result<(), PlayError> {
+        if self.outcome.is_some() {
+            return Err(PlayError::GameOver);
+        }
+        if mv.ply != self.ply() {
+            return Err(PlayError::OutOfSync);
+        }
+        let mv: xiangqi_core::Move = mv.iccs.parse().map_err(|_| PlayError::BadPosition)?;
+        let color: PieceColor = player.into();
+        let board = self.history.board();
+        if board.turn() != color {
+            return Err(PlayError::NotYourTurn);
+        }
+        if !board.get(mv.from).is_color(color) {
+            return Err(PlayError::NotYourPiece);
+        }
+        self.history.play(mv).map_err(|_| PlayError::IllegalMove)?;
+        // Making a move declines the opponent's offer
+        self.draw_offer = None;
+        Ok(())
     }
 
     fn is_expired(&self) -> bool {
-        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
-        let diff = now - self.last;
-        diff > EXPIRE
+        now().saturating_sub(self.last) > EXPIRE
     }
 }
 
@@ -67,7 +168,14 @@ fn connect(
             let response = match room.next {
                 RoomStatus::One(player) => {
                     info!("Player joined room {}", req.room);
-                    Some(ConnectResponse { player, ok: true })
+                    let token = room.sit(player);
+                    // The player who created the room is waiting for this
+                    room.push(!player, RoomUpdate::Joined);
+                    Some(ConnectResponse {
+                        player,
+                        token,
+                        ok: true,
+                    })
                 }
                 RoomStatus::Full => {
                     warn!("Room {} is full, joining rejected", req.room);
@@ -84,7 +192,12 @@ fn connect(
             if let RoomStatus::One(player) = room.next {
                 info!("New room {} created, player joined", req.room);
                 let player = !player;
-                Some(ConnectResponse { player, ok: true })
+                let token = room.sit(player);
+                Some(ConnectResponse {
+                    player,
+                    token,
+                    ok: true,
+                })
             } else {
                 unreachable!()
             }
@@ -97,39 +210,210 @@ fn connect(
 }
 
 #[post("/play", data = "<req>")]
-fn play(games: &State<Games>, req: Json<PlayRequest>) -> Status {
+fn play(
+    games: &State<Games>,
+    req: Json<PlayRequest>,
+) -> Result<Status, status::Custom<Json<PlayError>>> {
     if let Some(room) = games.rooms.write().unwrap().get_mut(&req.room) {
-        room.update();
+        let player = room
+            .player(req.token)
+            .map_err(|err| status::Custom(Status::Forbidden, Json(err)))?;
+
+        if let Err(err) = room.play(player, &req.mv) {
+            warn!(
+                "Move {:?} in room {} was rejected: {}",
+                req.mv, req.room, err
+            );
+            // The rejected player is sent the authoritative state to roll back to
+            let resync = room.resync();
+            room.push(player, RoomUpdate::Resync(resync));
+            return Err(status::Custom(Status::UnprocessableEntity, Json(err)));
+        }
 
         // Here player is flipped because the move is only effective on the opponent side
-        let player = !req.player;
-        room.get(player).push_back(req.board.clone());
-        info!("Update room {} to {:?}", req.room, req.board);
-        Status::Accepted
+        info!("Update room {} with {:?}", req.room, req.mv);
+        room.push(!player, RoomUpdate::Move(req.mv.clone()));
+        if let Some(outcome) = room.history.outcome(&GameRules::default()) {
+            info!("Room {} finished: {}", req.room, outcome);
+            room.finish(outcome);
+        }
+        Ok(Status::Accepted)
     } else {
         warn!("Update room {} was rejected", req.room);
-        Status::ServiceUnavailable
+        Err(status::Custom(
+            Status::ServiceUnavailable,
+            Json(PlayError::RoomNotFound),
+        ))
     }
 }
 
+#[post("/resign", data = "<req>")]
+fn resign(
+    games: &State<Games>,
+    req: Json<ResignRequest>,
+) -> Result<Status, status::Custom<Json<PlayError>>> {
+    let mut rooms = games.rooms.write().unwrap();
+    let room = rooms.get_mut(&req.room).ok_or(status::Custom(
+        Status::ServiceUnavailable,
+        Json(PlayError::RoomNotFound),
+    ))?;
+    let player = room
+        .player(req.token)
+        .map_err(|err| status::Custom(Status::Forbidden, Json(err)))?;
+    if room.outcome.is_some() {
+        return Err(status::Custom(
+            Status::UnprocessableEntity,
+            Json(PlayError::GameOver),
+        ));
+    }
+
+    info!("Player {} resigned in room {}", player, req.room);
+    let winner = PieceColor::from(!player);
+    room.finish(GameOutcome::wins(winner, WinReason::Resignation));
+    Ok(Status::Accepted)
+}
+
+#[post("/draw", data = "<req>")]
+fn draw(
+    games: &State<Games>,
+    req: Json<DrawRequest>,
+) -> Result<Status, status::Custom<Json<PlayError>>> {
+    let mut rooms = games.rooms.write().unwrap();
+    let room = rooms.get_mut(&req.room).ok_or(status::Custom(
+        Status::ServiceUnavailable,
+        Json(PlayError::RoomNotFound),
+    ))?;
+    let player = room
+        .player(req.token)
+        .map_err(|err| status::Custom(Status::Forbidden, Json(err)))?;
+    if room.outcome.is_some() {
+        return Err(status::Custom(
+            Status::UnprocessableEntity,
+            Json(PlayError::GameOver),
+        ));
+    }
+
+    if room.draw_offer == Some(!player) {
+        info!("Draw agreed in room {}", req.room);
+        room.finish(GameOutcome::Draw(DrawReason::Agreement));
+    } else {
+        info!("Player {} offered a draw in room {}", player, req.room);
+        room.draw_offer = Some(player);
+        room.push(!player, RoomUpdate::DrawOffered);
+    }
+    Ok(Status::Accepted)
+}
+
 #[get("/query", data = "<req>")]
-fn query(games: &State<Games>, req: Json<QueryRequest>) -> Json<QueryResponse> {
-    let board = if let Some(room) = games.rooms.write().unwrap().get_mut(&req.room) {
-        room.update();
+fn query(games: &State<Games>, req: Json<QueryRequest>) -> Result<Json<QueryResponse>, Status> {
+    let update = if let Some(room) = games.rooms.write().unwrap().get_mut(&req.room) {
+        let player = room.player(req.token).map_err(|_| Status::Forbidden)?;
 
         info!("Query room {}", req.room);
-        room.get(req.player).pop_front()
+        room.get(player).pop_front()
     } else {
         info!("Query room {} was rejected", req.room);
+        Some(RoomUpdate::Closed)
+    };
+    Ok(Json(QueryResponse { update }))
+}
+
+#[get("/sync", data = "<req>")]
+fn sync(games: &State<Games>, req: Json<QueryRequest>) -> Option<Json<Resync>> {
+    if let Some(room) = games.rooms.write().unwrap().get_mut(&req.room) {
+        let player = room.player(req.token).ok()?;
+
+        info!("Resync room {}", req.room);
+        // Anything still queued is covered by the full state
+        room.get(player).clear();
+        Some(Json(room.resync()))
+    } else {
+        info!("Resync room {} was rejected", req.room);
         None
+    }
+}
+
+#[get("/events/<id>/<token>")]
+fn events(
+    games: &State<Games>,
+    id: RoomId,
+    token: Token,
+    mut shutdown: Shutdown,
+) -> Option<EventStream![]> {
+    let rooms = games.rooms.clone();
+    let (notify, player) = {
+        let mut rooms = rooms.write().unwrap();
+        let room = rooms.get_mut(&id)?;
+        (room.notify.clone(), room.player(token).ok()?)
     };
-    Json(QueryResponse { board })
+    info!("Streaming room {} to player {}", id, player);
+
+    Some(
+        EventStream! {
+            loop {
+                // Registered before the queue is checked, so no push in between is missed
+                let notified = notify.notified();
+                rocket::tokio::pin!(notified);
+                notified.as_mut().enable();
+
+                let update = rooms.write().unwrap().get_mut(&id).map(|room| {
+                    // A live stream keeps the room from expiring
+                    room.seen(player);
+                    room.get(player).pop_front()
+                });
+                match update {
+                    Some(Some(update)) => yield Event::json(&update),
+                    Some(None) => {
+                        rocket::tokio::select! {
+                            _ = notified => {}
+                            _ = sleep(HEARTBEAT) => {}
+                            _ = &mut shutdown => break,
+                        }
+                    }
+                    None => {
+                        info!("Room {} closed, ending stream", id);
+                        yield Event::json(&RoomUpdate::Closed);
+                        break;
+                    }
+                }
+            }
+        }
+        .heartbeat(HEARTBEAT),
+    )
+}
+
+/// The game played in a room so far, as a PGN record for archiving.
+///
+/// Public on purpose, so that anyone told the room can follow the game. It changes nothing.
+#[get("/pgn/<id>")]
+fn pgn(games: &State<Games>, id: RoomId) -> Option<String> {
+    let rooms = games.rooms.read().unwrap();
+    let room = rooms.get(&id)?;
+    let mut record = GameRecord::new(room.history.clone());
+    record.set_tag("Event", "Online game");
+    record.set_tag("Site", format!("Room {}", id));
+    record.set_tag("Result", pgn_result(room.outcome));
+    if let Some(outcome) = room.outcome {
+        record.set_tag("Termination", outcome.to_string());
+    }
+    Some(record.to_pgn(MoveFormat::Iccs))
 }
 
 #[post("/disconnect", data = "<req>")]
-fn disconnect(games: &State<Games>, req: Json<DisconnectRequest>) {
+fn disconnect(
+    games: &State<Games>,
+    req: Json<DisconnectRequest>,
+) -> Result<Status, status::Custom<Json<PlayError>>> {
+    let mut rooms = games.rooms.write().unwrap();
+    let room = rooms.get_mut(&req.room).ok_or(status::Custom(
+        Status::ServiceUnavailable,
+        Json(PlayError::RoomNotFound),
+    ))?;
+    room.player(req.token)
+        .map_err(|err| status::Custom(Status::Forbidden, Json(err)))?;
     info!("Disconnect from room {}", req.room);
-    games.rooms.write().unwrap().remove(&req.room);
+    rooms.remove(&req.room);
+    Ok(Status::Accepted)
 }
 
 #[launch]
@@ -139,18 +423,27 @@ fn rocket() -> _ {
 
     std::thread::spawn(move || loop {
         let mut destruct = Vec::new();
-        rooms_clone.write().unwrap().iter().for_each(|(id, room)| {
-            if room.is_expired() {
-                destruct.push(*id);
-            }
-        });
+        rooms_clone
+            .write()
+            .unwrap()
+            .iter_mut()
+            .for_each(|(id, room)| {
+                if room.is_expired() {
+                    destruct.push(*id);
+                } else if let Some(player) = room.timed_out() {
+                    info!("Player {} timed out in room {}", player, id);
+                    let winner = PieceColor::from(!player);
+                    room.finish(GameOutcome::wins(winner, WinReason::Timeout));
+                }
+            });
         for id in destruct.into_iter() {
             rooms_clone.write().unwrap().remove(&id);
         }
-        std::thread::sleep(EXPIRE);
+        std::thread::sleep(HEARTBEAT);
     });
 
-    rocket::build()
-        .manage(Games { rooms })
-        .mount("/", routes![connect, play, query, disconnect])
+    rocket::build().manage(Games { rooms }).mount(
+        "/",
+        routes![connect, play, resign, draw, query, sync, events, pgn, disconnect],
+    )
 }
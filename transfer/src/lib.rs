@@ -1,6 +1,35 @@
 pub use serde::{Deserialize, Serialize};
+pub use xiangqi_core::{DrawReason, GameOutcome, WinReason};
 
 pub type RoomId = u64;
+pub type Ply = u32;
+/// A secret the server hands each seat on connecting, which proves who sends a request.
+pub type Token = u64;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Move {
+    pub ply: Ply,
+    /// The move in ICCS notation, such as `h2e2`.
+    pub iccs: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Resync {
+    pub ply: Ply,
+    pub board: String,
+    pub outcome: Option<GameOutcome>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum RoomUpdate {
+    Move(Move),
+    Resync(Resync),
+    /// Sent to both players once the game is over.
+    Outcome(GameOutcome),
+    DrawOffered,
+    Joined,
+    Closed,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct ConnectRequest {
@@ -10,28 +39,74 @@ pub struct ConnectRequest {
 #[derive(Serialize, Deserialize, Default)]
 pub struct ConnectResponse {
     pub player: bool,
+    pub token: Token,
     pub ok: bool,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct PlayRequest {
     pub room: RoomId,
-    pub player: bool,
-    pub board: String,
+    pub token: Token,
+    pub mv: Move,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayError {
+    RoomNotFound,
+    BadPosition,
+    NotYourTurn,
+    NotYourPiece,
+    IllegalMove,
+    OutOfSync,
+    GameOver,
+    /// The token belongs to neither seat of the room.
+    BadToken,
+}
+
+impl std::fmt::Display for PlayError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PlayError::RoomNotFound => write!(f, "Room not found"),
+            PlayError::BadPosition => write!(f, "Bad position"),
+            PlayError::NotYourTurn => write!(f, "Not your turn"),
+            PlayError::NotYourPiece => write!(f, "Not your piece"),
+            PlayError::IllegalMove => write!(f, "Illegal move"),
+            PlayError::OutOfSync => write!(f, "Out of sync"),
+            PlayError::GameOver => write!(f, "The game is over"),
+            PlayError::BadToken => write!(f, "Bad token"),
+        }
+    }
+}
+
+impl std::error::Error for PlayError {}
+
+/// Resigns the game for the player holding `token`.
+#[derive(Serialize, Deserialize)]
+pub struct ResignRequest {
+    pub room: RoomId,
+    pub token: Token,
+}
+
+/// Offers a draw, or accepts the one the opponent offered.
+#[derive(Serialize, Deserialize)]
+pub struct DrawRequest {
+    pub room: RoomId,
+    pub token: Token,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct QueryRequest {
     pub room: RoomId,
-    pub player: bool,
+    pub token: Token,
 }
 
 #[derive(Serialize, Deserialize, Default)]
 pub struct QueryResponse {
-    pub board: Option<String>,
+    pub update: Option<RoomUpdate>,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct DisconnectRequest {
     pub room: RoomId,
+    pub token: Token,
 }
@@ -0,0 +1,21 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use xiangqi_core::*;
+
+fn perft(c: &mut Criterion) {
+    let board = Board::default();
+    c.bench_function("perft 3", |b| b.iter(|| black_box(&board).perft(3)));
+}
+
+fn legal_moves(c: &mut Criterion) {
+    let board = Board::from_fen(
+        "r1bakab1r/9/1cn3nc1/p1p1p1p1p/9/9/P1P1P1P1P/1CN3NC1/9/R1BAKAB1R w - - 4 3",
+    )
+    .unwrap();
+    c.bench_function("legal moves", |b| {
+        b.iter(|| black_box(&board).legal_moves())
+    });
+    c.bench_function("is check", |b| b.iter(|| black_box(&board).is_check()));
+}
+
+criterion_group!(benches, perft, legal_moves);
+criterion_main!(benches);
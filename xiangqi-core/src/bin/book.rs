@@ -0,0 +1,75 @@
+use std::path::PathBuf;
+use xiangqi_core::*;
+
+/// How deep into each game moves are taken when not told otherwise.
+const DEFAULT_PLIES: usize = 20;
+
+fn usage() -> ! {
+    eprintln!("Usage: book <pgn directory> <output> [--plies <n>]");
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let mut paths = Vec::new();
+    let mut plies = DEFAULT_PLIES;
+    while let Some(arg) = args.next() {
+        if arg == "--plies" {
+            plies = args
+                .next()
+                .and_then(|plies| plies.parse().ok())
+                .unwrap_or_else(|| usage());
+        } else {
+            paths.push(PathBuf::from(arg));
+        }
+    }
+    let [dir, output] = <[PathBuf; 2]>::try_from(paths).unwrap_or_else(|_| usage());
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&dir)
+        .unwrap_or_else(|err| {
+            eprintln!("Unable to read {}: {}", dir.display(), err);
+            std::process::exit(1);
+        })
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("pgn"))
+        })
+        .collect();
+    files.sort();
+
+    let mut book = OpeningBook::with_names();
+    let (mut games, mut skipped) = (0, 0);
+    for file in files {
+        let text = match std::fs::read_to_string(&file) {
+            Ok(text) => text,
+            Err(err) => {
+                eprintln!("Skipping {}: {}", file.display(), err);
+                continue;
+            }
+        };
+        for (i, record) in GameRecord::read_all(&text).into_iter().enumerate() {
+            match record {
+                Ok(record) => {
+                    book.add_game(&record, plies);
+                    games += 1;
+                }
+                Err(err) => {
+                    eprintln!("Skipping game {} of {}: {}", i + 1, file.display(), err);
+                    skipped += 1;
+                }
+            }
+        }
+    }
+
+    if let Err(err) = book.save(&output) {
+        eprintln!("Unable to write {}: {}", output.display(), err);
+        std::process::exit(1);
+    }
+    println!(
+        "Read {} games, skipped {}, {} positions in the book",
+        games,
+        skipped,
+        book.len()
+    );
+}
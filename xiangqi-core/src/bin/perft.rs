@@ -0,0 +1,49 @@
+use std::time::Instant;
+use xiangqi_core::*;
+
+fn usage() -> ! {
+    eprintln!("Usage: perft <depth> [--divide] [fen]");
+    std::process::exit(2);
+}
+
+fn main() {
+    let mut depth = None;
+    let mut divide = false;
+    let mut board = None;
+    for arg in std::env::args().skip(1) {
+        if arg == "--divide" {
+            divide = true;
+        } else if depth.is_none() {
+            depth = Some(arg.parse::<usize>().unwrap_or_else(|_| usage()));
+        } else if board.is_none() {
+            board = Some(Board::from_fen(&arg).unwrap_or_else(|err| {
+                eprintln!("Unable to read FEN: {}", err);
+                std::process::exit(1);
+            }));
+        } else {
+            usage();
+        }
+    }
+    let depth = depth.unwrap_or_else(|| usage());
+    let board = board.unwrap_or_default();
+
+    let start = Instant::now();
+    let nodes = if divide {
+        let divided = board.perft_divide(depth);
+        for (mv, nodes) in divided.iter() {
+            println!("{}: {}", mv, nodes);
+        }
+        println!();
+        divided.iter().map(|(_, nodes)| nodes).sum()
+    } else {
+        board.perft(depth)
+    };
+    let elapsed = start.elapsed();
+
+    println!("Nodes: {}", nodes);
+    println!(
+        "Time: {:.3}s ({:.0} nodes/s)",
+        elapsed.as_secs_f64(),
+        nodes as f64 / elapsed.as_secs_f64()
+    );
+}
@@ -0,0 +1,559 @@
+use super::*;
+
+pub const SQUARES: usize = RANKS * FILES;
+/// The most moves a side can have with no more than a full set of pieces: 17 for each rook and
+/// cannon, 8 for each knight, 4 for each bishop and advisor, 5 for the king counting the capture
+/// of the other king, and 3 for each pawn, which adds up to 120.
+pub const MAX_MOVES: usize = 128;
+
+#[derive(Debug, Clone)]
+pub struct Board {
+    pub(crate) content: [Piece; SQUARES],
+    pub(crate) kings: [Position; 2],
+    pub(crate) turn: PieceColor,
+    pub(crate) halfmove: u32,
+    pub(crate) fullmove: u32,
+    pub(crate) zobrist: u64,
+}
+
+/// A fixed-capacity move buffer, so that move generation never allocates.
+#[derive(Clone)]
+pub struct MoveList {
+    moves: [Move; MAX_MOVES],
+    len: usize,
+}
+
+impl Default for MoveList {
+    fn default() -> Self {
+        Self {
+            moves: [Move::new(Position::new(0, 0), Position::new(0, 0)); MAX_MOVES],
+            len: 0,
+        }
+    }
+}
+
+impl MoveList {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, mv: Move) {
+        debug_assert!(self.len < MAX_MOVES, "more than {} moves", MAX_MOVES);
+        self.moves[self.len] = mv;
+        self.len += 1;
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    pub fn retain(&mut self, mut f: impl FnMut(Move) -> bool) {
+        let mut len = 0;
+        for i in 0..self.len {
+            if f(self.moves[i]) {
+                self.moves[len] = self.moves[i];
+                len += 1;
+            }
+        }
+        self.len = len;
+    }
+}
+
+impl std::ops::Deref for MoveList {
+    type Target = [Move];
+    fn deref(&self) -> &[Move] {
+        &self.moves[..self.len]
+    }
+}
+
+impl std::ops::DerefMut for MoveList {
+    fn deref_mut(&mut self) -> &mut [Move] {
+        &mut self.moves[..self.len]
+    }
+}
+
+impl std::fmt::Debug for MoveList {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+pub(crate) fn index(pos: Position) -> usize {
+    pos.rank() * FILES + pos.file()
+}
+
+fn forward(color: PieceColor) -> MoveDir {
+    match color {
+        PieceColor::Red => MoveDir::Up,
+        PieceColor::Black => MoveDir::Down,
+    }
+}
+
+pub(crate) fn crossed_river(pos: Position, color: PieceColor) -> bool {
+    match color {
+        PieceColor::Red => pos.rank_int() > 4,
+        PieceColor::Black => pos.rank_int() <= 4,
+    }
+}
+
+impl Default for Board {
+    fn default() -> Self {
+        let mut content = [Piece::empty(); SQUARES];
+        for (color, rank) in [(PieceColor::Red, 0), (PieceColor::Black, 9)] {
+            for (file, kind) in [
+                PieceKind::Rook,
+                PieceKind::Knight,
+                PieceKind::Bishop,
+                PieceKind::Advisor,
+                PieceKind::King,
+                PieceKind::Advisor,
+                PieceKind::Bishop,
+                PieceKind::Knight,
+                PieceKind::Rook,
+            ]
+            .into_iter()
+            .enumerate()
+            {
+                content[index(Position::new(rank, file))] = Piece::new(kind, color);
+            }
+        }
+        for (color, rank) in [(PieceColor::Red, 2), (PieceColor::Black, 7)] {
+            for file in [1, 7] {
+                content[index(Position::new(rank, file))] = Piece::new(PieceKind::Cannon, color);
+            }
+        }
+        for (color, rank) in [(PieceColor::Red, 3), (PieceColor::Black, 6)] {
+            for file in (0..FILES).step_by(2) {
+                content[index(Position::new(rank, file))] = Piece::new(PieceKind::Pawn, color);
+            }
+        }
+
+        let kings = [Position::new(0, 4), Position::new(9, 4)];
+
+        Self::from_parts(content, kings, PieceColor::Red, 0, 1)
+    }
+}
+
+impl Board {
+    pub(crate) fn from_parts(
+        content: [Piece; SQUARES],
+        kings: [Position; 2],
+        turn: PieceColor,
+        halfmove: u32,
+        fullmove: u32,
+    ) -> Self {
+        let mut board = Self {
+            content,
+            kings,
+            turn,
+            halfmove,
+            fullmove,
+            zobrist: 0,
+        };
+        board.zobrist = board.compute_zobrist();
+        board
+    }
+
+    pub fn get(&self, pos: Position) -> Piece {
+        self.content[index(pos)]
+    }
+
+    /// Puts `piece` on `pos`, returning what was there.
+    pub fn set(&mut self, pos: Position, piece: Piece) -> Piece {
+        let index = index(pos);
+        let prev = std::mem::replace(&mut self.content[index], piece);
+        self.zobrist ^= zobrist_key(prev, index) ^ zobrist_key(piece, index);
+        prev
+    }
+
+    pub fn force(&mut self, from: Position, to: Position) {
+        let piece = self.set(from, Piece::empty());
+        let captured = self.set(to, piece);
+        if captured.is_empty() {
+            self.halfmove += 1;
+        } else {
+            self.halfmove = 0;
+        }
+        if piece.is_kind(PieceKind::King) {
+            self.kings[Into::<u8>::into(piece.color().unwrap()) as usize] = to;
+        }
+    }
+
+    pub fn turn(&self) -> PieceColor {
+        self.turn
+    }
+
+    pub fn next_turn(&mut self) {
+        self.turn = self.turn.opposite();
+        self.zobrist ^= ZOBRIST_TURN;
+        if self.turn == PieceColor::Red {
+            self.fullmove += 1;
+        }
+    }
+
+    /// Plies played since the last capture.
+    pub fn halfmove(&self) -> u32 {
+        self.halfmove
+    }
+
+    /// The move number, starting at 1 and increasing after each move of Black.
+    pub fn fullmove(&self) -> u32 {
+        self.fullmove
+    }
+
+    fn test_move(&self, from: Position, to: Position, result: &mut MoveList) {
+        if let Some(to) = to.legal() {
+            if self.get(from).is_steppable(self.get(to)) {
+                result.push(Move::new(from, to));
+            }
+        }
+    }
+
+    fn reachable_pawn(&self, from: Position, result: &mut MoveList) {
+        let piece = self.get(from);
+        debug_assert!(piece.is_kind(PieceKind::Pawn));
+        let color = piece.color().unwrap();
+        if crossed_river(from, color) {
+            self.test_move(from, from + MoveDir::Left.into(), result);
+            self.test_move(from, from + MoveDir::Right.into(), result);
+        }
+        // Also move forward
+        self.test_move(from, from + forward(color).into(), result);
+    }
+
+    fn reachable_cannon(&self, from: Position, result: &mut MoveList) {
+        let piece = self.get(from);
+        debug_assert!(piece.is_kind(PieceKind::Cannon));
+        for dir in MOVE_DIRS {
+            let mut to = from;
+            loop {
+                to = to + dir.into();
+                if let Some(to) = to.legal() {
+                    if self.get(to).is_empty() {
+                        result.push(Move::new(from, to));
+                    } else {
+                        // here, the non-empty square is skipped in the following loop
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+            loop {
+                to = to + dir.into();
+                if let Some(to) = to.legal() {
+                    let to_piece = self.get(to);
+                    if !to_piece.is_empty() {
+                        if to_piece.is_enemy(piece) {
+                            result.push(Move::new(from, to));
+                        }
+                        break;
+                    }
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    fn reachable_king(&self, from: Position, result: &mut MoveList) {
+        let piece = self.get(from);
+        debug_assert!(piece.is_kind(PieceKind::King));
+        let color = piece.color().unwrap();
+        for dir in MOVE_DIRS {
+            let to = from + dir.into();
+            if to.in_palace(color) {
+                self.test_move(from, to, result);
+            }
+        }
+        let mut to = from;
+        loop {
+            to = to + forward(color).into();
+            if let Some(to) = to.legal() {
+                let to_piece = self.get(to);
+                if !to_piece.is_empty() {
+                    if to_piece.is_kind(PieceKind::King) {
+                        result.push(Move::new(from, to));
+                    }
+                    break;
+                }
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn reachable_advisor(&self, from: Position, result: &mut MoveList) {
+        let piece = self.get(from);
+        debug_assert!(piece.is_kind(PieceKind::Advisor));
+        let color = piece.color().unwrap();
+        for dir in DIAG_DIRS {
+            let to = from + dir.into();
+            if to.in_palace(color) {
+                self.test_move(from, to, result);
+            }
+        }
+    }
+
+    fn reachable_bishop(&self, from: Position, result: &mut MoveList) {
+        let piece = self.get(from);
+        debug_assert!(piece.is_kind(PieceKind::Bishop));
+        let color = piece.color().unwrap();
+        for dir in DIAG_DIRS {
+            if let Some(mid) = (from + dir.into()).legal() {
+                if self.get(mid).is_empty() {
+                    let to = from + Into::<Position>::into(dir) * 2;
+                    if to.legal().is_some() && !crossed_river(to, color) {
+                        self.test_move(from, to, result);
+                    }
+                }
+            }
+        }
+    }
+
+    fn reachable_knight(&self, from: Position, result: &mut MoveList) {
+        let piece = self.get(from);
+        debug_assert!(piece.is_kind(PieceKind::Knight));
+        for dir in MOVE_DIRS {
+            if let Some(mid) = (from + dir.into()).legal() {
+                if self.get(mid).is_empty() {
+                    for co_dir in dir.corresponding() {
+                        self.test_move(from, from + dir.into() + co_dir.into(), result);
+                    }
+                }
+            }
+        }
+    }
+
+    fn reachable_rook(&self, from: Position, result: &mut MoveList) {
+        let piece = self.get(from);
+        debug_assert!(piece.is_kind(PieceKind::Rook));
+        for dir in MOVE_DIRS {
+            let mut to = from;
+            loop {
+                to = to + dir.into();
+                if let Some(to) = to.legal() {
+                    let to_piece = self.get(to);
+                    if !to_piece.is_empty() {
+                        if to_piece.is_enemy(piece) {
+                            result.push(Move::new(from, to));
+                        }
+                        break;
+                    } else {
+                        result.push(Move::new(from, to));
+                    }
+                } else {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Appends the moves of the piece on `from`, ignoring whether they leave its king in check.
+    pub fn reachable_into(&self, from: Position, result: &mut MoveList) {
+        match self.get(from).kind() {
+            PieceKind::Empty => {}
+            PieceKind::Pawn => self.reachable_pawn(from, result),
+            PieceKind::Cannon => self.reachable_cannon(from, result),
+            PieceKind::King => self.reachable_king(from, result),
+            PieceKind::Advisor => self.reachable_advisor(from, result),
+            PieceKind::Bishop => self.reachable_bishop(from, result),
+            PieceKind::Knight => self.reachable_knight(from, result),
+            PieceKind::Rook => self.reachable_rook(from, result),
+        }
+    }
+
+    pub fn reachable(&self, from: Position) -> HashSet<Position> {
+        let mut result = MoveList::new();
+        self.reachable_into(from, &mut result);
+        result.iter().map(|mv| mv.to).collect()
+    }
+
+    /// Appends the moves of every piece of the side to move, ignoring checks.
+    pub fn pseudo_moves(&self, result: &mut MoveList) {
+        for (i, piece) in self.content.iter().enumerate() {
+            if piece.is_color(self.turn) {
+                self.reachable_into(Position::new(i / FILES, i % FILES), result);
+            }
+        }
+    }
+
+    pub fn king(&self, color: PieceColor) -> Position {
+        self.kings[Into::<u8>::into(color) as usize]
+    }
+
+    fn first_piece(&self, from: Position, dir: MoveDir) -> Option<(Position, Piece)> {
+        let mut pos = from;
+        loop {
+            pos = (pos + dir.into()).legal()?;
+            let piece = self.get(pos);
+            if !piece.is_empty() {
+                return Some((pos, piece));
+            }
+        }
+    }
+
+    /// Whether any piece of `by` could move onto `target`.
+    pub fn is_attacked(&self, target: Position, by: PieceColor) -> bool {
+        let is_enemy = |pos: Position, kind: PieceKind| {
+            pos.legal()
+                .map(|pos| {
+                    let piece = self.get(pos);
+                    piece.is_color(by) && piece.is_kind(kind)
+                })
+                .unwrap_or_default()
+        };
+
+        for dir in MOVE_DIRS {
+            if let Some((pos, piece)) = self.first_piece(target, dir) {
+                if piece.is_color(by) {
+                    match piece.kind() {
+                        PieceKind::Rook => return true,
+                        // The kings facing each other, or a step inside the palace
+                        PieceKind::King
+                            if (self.get(target).is_kind(PieceKind::King)
+                                && Position::from(dir) == -Position::from(forward(by)))
+                                || (pos - target == dir.into() && target.in_palace(by)) =>
+                        {
+                            return true
+                        }
+                        PieceKind::Pawn
+                            if pos - target == dir.into()
+                                && (Position::from(dir) == -Position::from(forward(by))
+                                    || (dir != forward(by) && crossed_river(pos, by))) =>
+                        {
+                            return true
+                        }
+                        _ => {}
+                    }
+                }
+                if let Some((_, piece)) = self.first_piece(pos, dir) {
+                    if piece.is_color(by) && piece.is_kind(PieceKind::Cannon) {
+                        return true;
+                    }
+                }
+            }
+        }
+
+        for diag in DIAG_DIRS {
+            let leg = target + diag.into();
+            if leg.legal().is_none() {
+                continue;
+            }
+            if target.in_palace(by) && is_enemy(leg, PieceKind::Advisor) {
+                return true;
+            }
+            if self.get(leg).is_empty() {
+                // A knight attacks through the leg diagonally next to its target
+                let (rank, file) = ((leg - target).rank_int(), (leg - target).file_int());
+                for knight in [
+                    target + Position::new_int(rank * 2, file),
+                    target + Position::new_int(rank, file * 2),
+                ] {
+                    if is_enemy(knight, PieceKind::Knight) {
+                        return true;
+                    }
+                }
+                if !crossed_river(target, by) && is_enemy(leg + diag.into(), PieceKind::Bishop) {
+                    return true;
+                }
+            }
+        }
+        false
+    }
+
+    pub fn is_check(&self) -> bool {
+        self.is_attacked(self.king(self.turn()), self.turn().opposite())
+    }
+}
+
+impl From<&Board> for String {
+    fn from(value: &Board) -> Self {
+        let mut result = String::new();
+        for rank in 0..RANKS {
+            for file in 0..FILES {
+                let pos = Position::new(rank, file);
+                let piece = value.get(pos);
+                result.push_str(&Into::<String>::into(piece));
+            }
+        }
+        result.push_str(&value.kings.len().to_string());
+        result.push('/');
+        result.push_str(
+            &value
+                .kings
+                .iter()
+                .map(|pos| (*pos).into())
+                .collect::<Vec<String>>()
+                .join(""),
+        );
+        result.push(value.turn.into());
+        result
+    }
+}
+
+impl TryFrom<&str> for Board {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Error> {
+        let board = Board::parse_unchecked(value)?;
+        board.validate().map_err(Error::InvalidPosition)?;
+        Ok(board)
+    }
+}
+
+impl Board {
+    /// Reads the format of `String::from(&Board)` without [`Board::validate`], for positions
+    /// that only exist to exercise the rules.
+    ///
+    /// Only more pieces than a full set are rejected, since they could have more moves than a
+    /// [`MoveList`] holds.
+    pub fn parse_unchecked(value: &str) -> Result<Self, Error> {
+        let chars: Vec<char> = value.chars().collect();
+        let take = |offset: usize, len: usize, expected| {
+            chars
+                .get(offset..offset + len)
+                .map(|chars| chars.iter().collect::<String>())
+                .ok_or(Error::UnexpectedEnd {
+                    offset: chars.len(),
+                    expected,
+                })
+        };
+
+        let mut offset = 0;
+        let mut content = [Piece::empty(); SQUARES];
+        for piece in content.iter_mut() {
+            *piece = Piece::try_from(take(offset, 2, "piece")?.as_str())
+                .map_err(|err| err.at(offset))?;
+            offset += 2;
+        }
+        // The format lists a fixed number of kings
+        for (expected, what) in [('2', "king count of 2"), ('/', "'/'")] {
+            let c = take(offset, 1, what)?.chars().next().unwrap();
+            if c != expected {
+                return Err(Error::BadChar {
+                    offset,
+                    c,
+                    expected: what,
+                });
+            }
+            offset += 1;
+        }
+        let mut kings = [Position::new(0, 0); 2];
+        for king in kings.iter_mut() {
+            *king = Position::try_from(take(offset, 2, "king position")?.as_str())
+                .map_err(|err| err.at(offset))?;
+            offset += 2;
+        }
+        let c = take(offset, 1, "side to move")?.chars().next().unwrap();
+        let turn = PieceColor::try_from(c).map_err(|err| err.at(offset))?;
+        if offset + 1 < chars.len() {
+            return Err(Error::TrailingInput { offset: offset + 1 });
+        }
+        let board = Board::from_parts(content, kings, turn, 0, 1);
+        let excess = board.excess_pieces();
+        if !excess.is_empty() {
+            return Err(Error::InvalidPosition(excess));
+        }
+        Ok(board)
+    }
+}
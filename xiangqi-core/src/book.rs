@@ -0,0 +1,339 @@
+use super::*;
+use std::collections::HashMap;
+use std::path::Path;
+
+const MAGIC: &[u8; 4] = b"XQBK";
+const VERSION: u8 = 1;
+
+/// Well known openings, each named after the position its moves lead to, with how often the
+/// computer should pick it.
+static OPENINGS: [(&str, &str, u32); 20] = [
+    ("中炮", "h2e2", 30),
+    ("中炮", "b2e2", 10),
+    ("中炮对屏风马", "h2e2 h9g7 h0g2 b9c7", 20),
+    ("中炮对反宫马", "h2e2 b9c7 h0g2 h7f7", 5),
+    ("中炮对单提马", "h2e2 b9a7", 2),
+    ("顺炮", "h2e2 h7e7", 6),
+    ("列炮", "h2e2 b7e7", 3),
+    ("飞相局", "c0e2", 15),
+    ("飞相局", "g0e2", 10),
+    ("飞相对左中炮", "c0e2 b7e7", 3),
+    ("仙人指路", "c3c4", 12),
+    ("仙人指路", "g3g4", 6),
+    ("仙人指路对卒底炮", "c3c4 b7c7", 5),
+    ("对兵局", "c3c4 c6c5", 3),
+    ("起马局", "b0c2", 8),
+    ("起马局", "h0g2", 6),
+    ("过宫炮", "h2d2", 3),
+    ("过宫炮", "b2f2", 2),
+    ("士角炮", "h2f2", 2),
+    ("士角炮", "b2d2", 2),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BookMove {
+    pub mv: Move,
+    /// How often the move is picked, relative to the other moves of its position.
+    pub weight: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BookError {
+    Io(String),
+    /// The file does not start with `XQBK`.
+    BadSignature,
+    UnsupportedVersion(u8),
+    /// The file ends inside a record, at the given offset.
+    Truncated(usize),
+    /// A move with a square off the board, at the given offset.
+    BadMove(usize),
+    /// A name that is not UTF-8, at the given offset.
+    BadName(usize),
+}
+
+impl std::fmt::Display for BookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            BookError::Io(err) => write!(f, "Unable to read the book: {}", err),
+            BookError::BadSignature => write!(f, "Not an opening book"),
+            BookError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported book version {}", version)
+            }
+            BookError::Truncated(offset) => write!(f, "File ends early at offset {}", offset),
+            BookError::BadMove(offset) => write!(f, "Bad move at offset {}", offset),
+            BookError::BadName(offset) => write!(f, "Bad opening name at offset {}", offset),
+        }
+    }
+}
+
+impl std::error::Error for BookError {}
+
+/// Candidate moves and opening names, looked up by position hash.
+///
+/// On disk a book is `XQBK` and a version byte, then a count and that many move records, then a
+/// count and that many name records. A move record is a position hash, one byte for each square
+/// of the move, counted from `a0` along the ranks, and a weight. A name record is a position hash
+/// and a UTF-8 name after its length. Numbers are little endian, hashes take eight bytes and the
+/// rest four.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OpeningBook {
+    moves: HashMap<u64, Vec<BookMove>>,
+    names: HashMap<u64, String>,
+}
+
+impl OpeningBook {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A book with the names of the well known openings, but no moves.
+    pub fn with_names() -> Self {
+        let mut book = Self::new();
+        for (name, line, _) in OPENINGS.iter() {
+            let mut board = Board::default();
+            for mv in line.split_whitespace() {
+                board.make_move(mv.parse().unwrap());
+            }
+            book.set_name(&board, *name);
+        }
+        book
+    }
+
+    /// The well known openings, with their names, ready to play.
+    pub fn standard() -> Self {
+        let mut book = Self::with_names();
+        for (_, line, weight) in OPENINGS.iter() {
+            let mut board = Board::default();
+            for mv in line.split_whitespace() {
+                let mv = mv.parse().unwrap();
+                book.add(&board, mv, *weight);
+                board.make_move(mv);
+            }
+        }
+        book
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty() && self.names.is_empty()
+    }
+
+    /// How many positions have moves.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    /// Adds `weight` to playing `mv` on `board`.
+    pub fn add(&mut self, board: &Board, mv: Move, weight: u32) {
+        sort(self.insert(board.zobrist(), BookMove { mv, weight }));
+    }
+
+    /// Adds a move without putting its position's moves back in order.
+    fn insert(&mut self, key: u64, book: BookMove) -> &mut Vec<BookMove> {
+        let moves = self.moves.entry(key).or_default();
+        match moves.iter_mut().find(|own| own.mv == book.mv) {
+            Some(own) => own.weight = own.weight.saturating_add(book.weight),
+            None => moves.push(book),
+        }
+        moves
+    }
+
+    /// Puts the moves of the positions `keys` back in order.
+    fn sort_keys(&mut self, keys: impl IntoIterator<Item = u64>) {
+        for key in keys {
+            if let Some(moves) = self.moves.get_mut(&key) {
+                sort(moves);
+            }
+        }
+    }
+
+    /// Adds the first `plies` moves of a game, weighing moves of the winner the most.
+    ///
+    /// Games that do not start from the usual position are left out.
+    pub fn add_game(&mut self, record: &GameRecord, plies: usize) {
+        let history = &record.history;
+        if *history.start() != Board::default() {
+            return;
+        }
+        let winner = match record.tag("Result") {
+            Some("1-0") => Some(PieceColor::Red),
+            Some("0-1") => Some(PieceColor::Black),
+            _ => None,
+        };
+        let mut keys = Vec::new();
+        for (board, mv) in history.replay().zip(history.moves()).take(plies) {
+            let weight = match winner {
+                Some(winner) if winner == board.turn() => 3,
+                Some(_) => 1,
+                None => 2,
+            };
+            self.insert(board.zobrist(), BookMove { mv, weight });
+            keys.push(board.zobrist());
+        }
+        self.sort_keys(keys);
+    }
+
+    pub fn set_name(&mut self, board: &Board, name: impl Into<String>) {
+        self.names.insert(board.zobrist(), name.into());
+    }
+
+    /// Adds the moves and names of `other`.
+    pub fn merge(&mut self, other: &OpeningBook) {
+        for (key, moves) in other.moves.iter() {
+            for book in moves {
+                self.insert(*key, *book);
+            }
+        }
+        self.sort_keys(other.moves.keys().copied());
+        for (key, name) in other.names.iter() {
+            self.names.entry(*key).or_insert_with(|| name.clone());
+        }
+    }
+
+    /// The legal book moves of `board`, most popular first.
+    pub fn moves(&self, board: &Board) -> Vec<BookMove> {
+        self.moves
+            .get(&board.zobrist())
+            .into_iter()
+            .flatten()
+            // Another position may have the same hash
+            .filter(|book| book.weight > 0 && board.is_legal(book.mv))
+            .copied()
+            .collect()
+    }
+
+    /// Picks one of the book moves of `board` at random, by weight.
+    pub fn pick(&self, board: &Board) -> Option<Move> {
+        let moves = self.moves(board);
+        let total: u64 = moves.iter().map(|book| book.weight as u64).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut left = random() % total;
+        for book in moves {
+            if left < book.weight as u64 {
+                return Some(book.mv);
+            }
+            left -= book.weight as u64;
+        }
+        None
+    }
+
+    pub fn name(&self, board: &Board) -> Option<&str> {
+        self.names.get(&board.zobrist()).map(String::as_str)
+    }
+
+    /// The name of the latest named position of a game.
+    pub fn opening(&self, history: &GameHistory) -> Option<&str> {
+        history
+            .replay()
+            .filter_map(|board| self.name(&board))
+            .last()
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.push(VERSION);
+        let mut moves: Vec<(u64, BookMove)> = self
+            .moves
+            .iter()
+            .flat_map(|(key, moves)| moves.iter().map(|book| (*key, *book)))
+            .collect();
+        moves.sort_by_key(|(key, book)| (*key, squares(book.mv)));
+        out.extend((moves.len() as u32).to_le_bytes());
+        for (key, book) in moves {
+            out.extend(key.to_le_bytes());
+            out.push(index(book.mv.from) as u8);
+            out.push(index(book.mv.to) as u8);
+            out.extend(book.weight.to_le_bytes());
+        }
+        let mut names: Vec<(&u64, &String)> = self.names.iter().collect();
+        names.sort();
+        out.extend((names.len() as u32).to_le_bytes());
+        for (key, name) in names {
+            out.extend(key.to_le_bytes());
+            out.extend((name.len() as u32).to_le_bytes());
+            out.extend(name.as_bytes());
+        }
+        out
+    }
+
+    pub fn read(data: &[u8]) -> Result<Self, BookError> {
+        if data.get(..4) != Some(MAGIC) {
+            return Err(BookError::BadSignature);
+        }
+        let mut reader = Reader { data, offset: 4 };
+        let version = reader.bytes(1)?[0];
+        if version != VERSION {
+            return Err(BookError::UnsupportedVersion(version));
+        }
+
+        let mut book = Self::new();
+        for _ in 0..reader.u32()? {
+            let key = reader.u64()?;
+            let offset = reader.offset;
+            let squares = reader.bytes(2)?;
+            let square = |index: u8| {
+                let index = index as usize;
+                Position::new(index / FILES, index % FILES)
+            };
+            if squares.iter().any(|index| *index as usize >= SQUARES) {
+                return Err(BookError::BadMove(offset));
+            }
+            let mv = Move::new(square(squares[0]), square(squares[1]));
+            let weight = reader.u32()?;
+            book.insert(key, BookMove { mv, weight });
+        }
+        book.moves.values_mut().for_each(|moves| sort(moves));
+        for _ in 0..reader.u32()? {
+            let key = reader.u64()?;
+            let len = reader.u32()? as usize;
+            let offset = reader.offset;
+            let name =
+                std::str::from_utf8(reader.bytes(len)?).map_err(|_| BookError::BadName(offset))?;
+            book.names.insert(key, name.to_string());
+        }
+        Ok(book)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, BookError> {
+        let data = std::fs::read(path).map_err(|err| BookError::Io(err.to_string()))?;
+        Self::read(&data)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), BookError> {
+        std::fs::write(path, self.to_bytes()).map_err(|err| BookError::Io(err.to_string()))
+    }
+}
+
+fn squares(mv: Move) -> (usize, usize) {
+    (index(mv.from), index(mv.to))
+}
+
+/// Most popular first, which also makes equal books compare equal.
+fn sort(moves: &mut [BookMove]) {
+    moves.sort_by_key(|book| (std::cmp::Reverse(book.weight), squares(book.mv)));
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], BookError> {
+        let bytes = self
+            .data
+            .get(self.offset..self.offset + len)
+            .ok_or(BookError::Truncated(self.offset))?;
+        self.offset += len;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, BookError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, BookError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
@@ -0,0 +1,121 @@
+use super::*;
+
+/// Why a move cannot be played.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IllegalReason {
+    OffBoard,
+    EmptySquare,
+    /// The piece cannot move to the target square.
+    Unreachable,
+    /// The move would leave the mover's king attacked, or the kings facing each other.
+    KingInCheck,
+}
+
+impl std::fmt::Display for IllegalReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            IllegalReason::OffBoard => write!(f, "the move leaves the board"),
+            IllegalReason::EmptySquare => write!(f, "there is no piece to move"),
+            IllegalReason::Unreachable => write!(f, "the piece cannot move there"),
+            IllegalReason::KingInCheck => write!(f, "the king would be in check"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A character that cannot appear here, with its offset in the input.
+    BadChar {
+        offset: usize,
+        c: char,
+        expected: &'static str,
+    },
+    /// The input stops at `offset` although `expected` should follow.
+    UnexpectedEnd {
+        offset: usize,
+        expected: &'static str,
+    },
+    /// Input is left over from `offset` on.
+    TrailingInput {
+        offset: usize,
+    },
+    IllegalMove {
+        mv: Move,
+        reason: IllegalReason,
+    },
+    /// The piece moved does not belong to the side to move.
+    WrongSide {
+        mv: Move,
+        turn: PieceColor,
+    },
+    /// A move in some notation that matches none of the legal moves.
+    UnknownMove(String),
+    InvalidPosition(Vec<Violation>),
+}
+
+impl Error {
+    /// Shifts the offset of a parsing error, for input that was read as part of a larger one.
+    pub fn at(self, start: usize) -> Self {
+        match self {
+            Error::BadChar {
+                offset,
+                c,
+                expected,
+            } => Error::BadChar {
+                offset: start + offset,
+                c,
+                expected,
+            },
+            Error::UnexpectedEnd { offset, expected } => Error::UnexpectedEnd {
+                offset: start + offset,
+                expected,
+            },
+            Error::TrailingInput { offset } => Error::TrailingInput {
+                offset: start + offset,
+            },
+            err => err,
+        }
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::BadChar {
+                offset,
+                c,
+                expected,
+            } => write!(
+                f,
+                "Expected {} at offset {}, found {:?}",
+                expected, offset, c
+            ),
+            Error::UnexpectedEnd { offset, expected } => {
+                write!(
+                    f,
+                    "Expected {} at offset {}, found the end",
+                    expected, offset
+                )
+            }
+            Error::TrailingInput { offset } => write!(f, "Unexpected input at offset {}", offset),
+            Error::IllegalMove { mv, reason } => write!(f, "Illegal move {}: {}", mv, reason),
+            Error::WrongSide { mv, turn } => {
+                write!(f, "Move {} does not move a piece of {:?}", mv, turn)
+            }
+            Error::UnknownMove(notation) => write!(f, "No legal move matches {:?}", notation),
+            Error::InvalidPosition(violations) => {
+                write!(f, "Invalid position: {}", join(violations))
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+pub(crate) fn join(violations: &[Violation]) -> String {
+    violations
+        .iter()
+        .map(|violation| violation.to_string())
+        .collect::<Vec<_>>()
+        .join("; ")
+}
@@ -0,0 +1,405 @@
+use super::*;
+use std::path::Path;
+
+/// The largest weight a file may set, either way, which keeps evaluations from overflowing.
+pub const MAX_WEIGHT: i32 = 10_000;
+
+/// Piece kinds as they are named in weight files, indexed like [`PieceKind`].
+const KIND_NAMES: [&str; 8] = [
+    "empty", "pawn", "cannon", "king", "advisor", "bishop", "knight", "rook",
+];
+
+#[rustfmt::skip]
+const PAWN_TABLE: [i32; SQUARES] = [
+     0,  2,  4,  6,  8,  6,  4,  2,  0,
+    10, 18, 28, 40, 50, 40, 28, 18, 10,
+     8, 14, 22, 30, 38, 30, 22, 14,  8,
+     6, 10, 16, 18, 20, 18, 16, 10,  6,
+     2,  6,  8,  8, 10,  8,  8,  6,  2,
+     0,  0,  2,  0,  4,  0,  2,  0,  0,
+     0,  0, -2,  0,  2,  0, -2,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const CANNON_TABLE: [i32; SQUARES] = [
+     4,  4,  0, -5, -6, -5,  0,  4,  4,
+     2,  2,  0, -4, -7, -4,  0,  2,  2,
+     1,  1,  0, -5, -4, -5,  0,  1,  1,
+     0,  3,  3,  2,  4,  2,  3,  3,  0,
+     0,  0,  0,  0,  4,  0,  0,  0,  0,
+    -1,  0,  3,  0,  4,  0,  3,  0, -1,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     1,  0,  4,  3,  5,  3,  4,  0,  1,
+     0,  1,  2,  2,  2,  2,  2,  1,  0,
+     0,  0,  1,  3,  3,  3,  1,  0,  0,
+];
+
+#[rustfmt::skip]
+const KING_TABLE: [i32; SQUARES] = [
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0, -9, -9, -9,  0,  0,  0,
+     0,  0,  0, -8, -8, -8,  0,  0,  0,
+     0,  0,  0,  1,  5,  1,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const ADVISOR_TABLE: [i32; SQUARES] = [
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0, -1,  0, -1,  0,  0,  0,
+     0,  0,  0,  0,  3,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const BISHOP_TABLE: [i32; SQUARES] = [
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0, -2,  0,  0,  0, -2,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+    -2,  0,  0,  0,  3,  0,  0,  0, -2,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+     0,  0,  0,  0,  0,  0,  0,  0,  0,
+];
+
+#[rustfmt::skip]
+const KNIGHT_TABLE: [i32; SQUARES] = [
+     2,  2,  2,  8,  2,  8,  2,  2,  2,
+     2,  8, 15,  9,  6,  9, 15,  8,  2,
+     4, 10, 11, 15, 11, 15, 11, 10,  4,
+     5, 20, 12, 19, 12, 19, 12, 20,  5,
+     2, 12, 11, 15, 16, 15, 11, 12,  2,
+     2, 10, 13, 14, 15, 14, 13, 10,  2,
+     4,  6, 10,  7, 10,  7, 10,  6,  4,
+     5,  4,  6,  7,  4,  7,  6,  4,  5,
+    -3,  2,  4,  5,-10,  5,  4,  2, -3,
+     0, -3,  2,  0,  2,  0,  2, -3,  0,
+];
+
+#[rustfmt::skip]
+const ROOK_TABLE: [i32; SQUARES] = [
+     6,  8,  7, 13, 14, 13,  7,  8,  6,
+     6, 12,  9, 16, 33, 16,  9, 12,  6,
+     6,  8,  7, 14, 16, 14,  7,  8,  6,
+     6, 13, 13, 16, 16, 16, 13, 13,  6,
+     8, 11, 11, 14, 15, 14, 11, 11,  8,
+     8, 12, 12, 14, 15, 14, 12, 12,  8,
+     4,  9,  4, 12, 14, 12,  4,  9,  4,
+    -2,  8,  4, 12, 12, 12,  4,  8, -2,
+     5,  8,  6, 12,  0, 12,  6,  8,  5,
+    -6,  6,  4, 12,  0, 12,  4,  6, -6,
+];
+
+/// Everything the evaluation is made of, in centipawn-like units.
+///
+/// Arrays are indexed by [`PieceKind`]. Piece-square tables are laid out as Red sees the board,
+/// Black's back rank first, and are mirrored for Black.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Weights {
+    pub material: [i32; 8],
+    pub tables: [[i32; SQUARES]; 8],
+    /// Per reachable square.
+    pub mobility: [i32; 8],
+    /// Extra value of a pawn that has crossed the river.
+    pub river_pawn: i32,
+    /// Penalty for every advisor short of two.
+    pub missing_advisor: i32,
+    /// Penalty for every elephant short of two.
+    pub missing_bishop: i32,
+    /// Penalty for every palace file down which an enemy rook or cannon bears on the palace.
+    pub open_file: i32,
+}
+
+impl Default for Weights {
+    fn default() -> Self {
+        Self {
+            material: [0, 30, 285, 0, 120, 120, 270, 600],
+            tables: [
+                [0; SQUARES],
+                PAWN_TABLE,
+                CANNON_TABLE,
+                KING_TABLE,
+                ADVISOR_TABLE,
+                BISHOP_TABLE,
+                KNIGHT_TABLE,
+                ROOK_TABLE,
+            ],
+            mobility: [0, 0, 1, 0, 0, 0, 4, 2],
+            river_pawn: 20,
+            missing_advisor: 25,
+            missing_bishop: 20,
+            open_file: 30,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    Io(String),
+    /// A line, counted from 1, that is neither a setting nor part of a table.
+    BadLine(usize),
+    UnknownKey {
+        line: usize,
+        key: String,
+    },
+    BadValue {
+        line: usize,
+        value: String,
+    },
+    /// A table that does not have a value for every square.
+    TableLength {
+        line: usize,
+        len: usize,
+    },
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(err) => write!(f, "Unable to read weights: {}", err),
+            ConfigError::BadLine(line) => write!(f, "Line {} is not a setting", line),
+            ConfigError::UnknownKey { line, key } => {
+                write!(f, "Unknown key {:?} on line {}", key, line)
+            }
+            ConfigError::BadValue { line, value } => {
+                write!(f, "Bad value {:?} on line {}", value, line)
+            }
+            ConfigError::TableLength { line, len } => write!(
+                f,
+                "The table on line {} has {} values instead of {}",
+                line, len, SQUARES
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn kind_index(line: usize, key: &str, name: &str) -> Result<usize, ConfigError> {
+    KIND_NAMES
+        .iter()
+        .skip(1)
+        .position(|kind| *kind == name)
+        .map(|index| index + 1)
+        .ok_or(ConfigError::UnknownKey {
+            line,
+            key: key.to_string(),
+        })
+}
+
+/// A table being read: the line it starts on, the piece kind and the values so far.
+type Table = (usize, usize, Vec<i32>);
+
+fn finish_table(table: Option<Table>, weights: &mut Weights) -> Result<(), ConfigError> {
+    if let Some((line, kind, values)) = table {
+        weights.tables[kind] =
+            values
+                .try_into()
+                .map_err(|values: Vec<i32>| ConfigError::TableLength {
+                    line,
+                    len: values.len(),
+                })?;
+    }
+    Ok(())
+}
+
+impl Weights {
+    /// Reads weights written as `key = value` lines, such as `material.rook = 600`, starting
+    /// from the defaults. A table lists its values after `table.<kind> =`, across any number of
+    /// lines. `#` starts a comment.
+    pub fn parse(config: &str) -> Result<Self, ConfigError> {
+        let mut weights = Weights::default();
+        let mut table: Option<Table> = None;
+
+        for (i, text) in config.lines().enumerate() {
+            let line = i + 1;
+            let text = text.split('#').next().unwrap().trim();
+            if text.is_empty() {
+                continue;
+            }
+            let (key, value) = match text.split_once('=') {
+                Some((key, value)) => (Some(key.trim()), value),
+                None => (None, text),
+            };
+            let values = value
+                .split_whitespace()
+                .map(|value| {
+                    value
+                        .parse::<i32>()
+                        .ok()
+                        .filter(|value| value.abs() <= MAX_WEIGHT)
+                        .ok_or(ConfigError::BadValue {
+                            line,
+                            value: value.to_string(),
+                        })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let Some(key) = key else {
+                table
+                    .as_mut()
+                    .ok_or(ConfigError::BadLine(line))?
+                    .2
+                    .extend(values);
+                continue;
+            };
+            finish_table(table.take(), &mut weights)?;
+
+            if let Some(name) = key.strip_prefix("table.") {
+                table = Some((line, kind_index(line, key, name)?, values));
+                continue;
+            }
+            let [value] = values[..] else {
+                return Err(ConfigError::BadValue {
+                    line,
+                    value: value.trim().to_string(),
+                });
+            };
+            let target = match key.split_once('.') {
+                Some(("material", name)) => &mut weights.material[kind_index(line, key, name)?],
+                Some(("mobility", name)) => &mut weights.mobility[kind_index(line, key, name)?],
+                None if key == "river_pawn" => &mut weights.river_pawn,
+                None if key == "missing_advisor" => &mut weights.missing_advisor,
+                None if key == "missing_bishop" => &mut weights.missing_bishop,
+                None if key == "open_file" => &mut weights.open_file,
+                _ => {
+                    return Err(ConfigError::UnknownKey {
+                        line,
+                        key: key.to_string(),
+                    })
+                }
+            };
+            *target = value;
+        }
+        finish_table(table, &mut weights)?;
+        Ok(weights)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, ConfigError> {
+        let config =
+            std::fs::read_to_string(path).map_err(|err| ConfigError::Io(err.to_string()))?;
+        Weights::parse(&config)
+    }
+
+    /// Writes every weight in the form [`Weights::parse`] reads.
+    pub fn to_config(&self) -> String {
+        let mut result = String::new();
+        for (key, values) in [("material", &self.material), ("mobility", &self.mobility)] {
+            for (name, value) in KIND_NAMES.iter().zip(values).skip(1) {
+                result.push_str(&format!("{}.{} = {}\n", key, name, value));
+            }
+        }
+        for (key, value) in [
+            ("river_pawn", self.river_pawn),
+            ("missing_advisor", self.missing_advisor),
+            ("missing_bishop", self.missing_bishop),
+            ("open_file", self.open_file),
+        ] {
+            result.push_str(&format!("{} = {}\n", key, value));
+        }
+        for (name, table) in KIND_NAMES.iter().zip(&self.tables).skip(1) {
+            result.push_str(&format!("table.{} =\n", name));
+            for rank in table.chunks(FILES) {
+                let rank: Vec<String> = rank.iter().map(|value| format!("{:3}", value)).collect();
+                result.push_str(&rank.join(" "));
+                result.push('\n');
+            }
+        }
+        result
+    }
+
+    pub(crate) fn value(&self, kind: PieceKind) -> i32 {
+        self.material[u8::from(kind) as usize]
+    }
+}
+
+/// Where a piece of `color` on `pos` is found in a piece-square table.
+fn table_index(pos: Position, color: PieceColor) -> usize {
+    let row = match color {
+        PieceColor::Red => RANKS - 1 - pos.rank(),
+        PieceColor::Black => pos.rank(),
+    };
+    row * FILES + pos.file()
+}
+
+impl Board {
+    /// Palace files of `color` down which an enemy rook, or an enemy cannon behind one screen,
+    /// looks into the palace.
+    fn open_files(&self, color: PieceColor) -> i32 {
+        let mut count = 0;
+        for file in 3..=5 {
+            // Walk away from the palace, towards the enemy
+            let mut pieces = (3..RANKS)
+                .map(|rank| match color {
+                    PieceColor::Red => rank,
+                    PieceColor::Black => RANKS - 1 - rank,
+                })
+                .map(|rank| self.get(Position::new(rank, file)))
+                .filter(|piece| !piece.is_empty());
+            let enemy = |piece: Option<Piece>, kind| {
+                piece.is_some_and(|piece| piece.is_kind(kind) && piece.is_color(color.opposite()))
+            };
+            let first = pieces.next();
+            if enemy(first, PieceKind::Rook)
+                || (first.is_some() && enemy(pieces.next(), PieceKind::Cannon))
+            {
+                count += 1;
+            }
+        }
+        count
+    }
+
+    /// Scores the position for the side to move.
+    pub fn evaluate(&self, weights: &Weights) -> i32 {
+        let mut scores = [0; 2];
+        let mut guards = [[0; 8]; 2];
+        let mut reachable = MoveList::new();
+        for (i, piece) in self.content.iter().enumerate() {
+            let Some(color) = piece.color() else {
+                continue;
+            };
+            let pos = Position::new(i / FILES, i % FILES);
+            let kind = u8::from(piece.kind()) as usize;
+            let mut value = weights.material[kind] + weights.tables[kind][table_index(pos, color)];
+            if piece.is_kind(PieceKind::Pawn) && crossed_river(pos, color) {
+                value += weights.river_pawn;
+            }
+            if weights.mobility[kind] != 0 {
+                reachable.clear();
+                self.reachable_into(pos, &mut reachable);
+                value += weights.mobility[kind] * reachable.len() as i32;
+            }
+            let side = u8::from(color) as usize;
+            scores[side] += value;
+            guards[side][kind] += 1;
+        }
+
+        for color in [PieceColor::Red, PieceColor::Black] {
+            let side = u8::from(color) as usize;
+            let advisors = guards[side][u8::from(PieceKind::Advisor) as usize];
+            let bishops = guards[side][u8::from(PieceKind::Bishop) as usize];
+            scores[side] -= (2 - advisors).max(0) * weights.missing_advisor
+                + (2 - bishops).max(0) * weights.missing_bishop
+                + self.open_files(color) * weights.open_file;
+        }
+
+        let side = u8::from(self.turn()) as usize;
+        scores[side] - scores[1 - side]
+    }
+}
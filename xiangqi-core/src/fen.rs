@@ -0,0 +1,189 @@
+use super::*;
+
+pub static START_FEN: &str =
+    "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w - - 0 1";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FenError {
+    /// A required field is missing, named by the field.
+    MissingField(&'static str),
+    /// The placement field does not have 10 ranks.
+    RankCount(usize),
+    /// A rank, counted from Black's side, does not describe exactly 9 files.
+    RankLength {
+        rank: usize,
+        files: usize,
+    },
+    /// An unknown piece character at the given byte offset.
+    BadPiece {
+        offset: usize,
+        c: char,
+    },
+    BadTurn(String),
+    BadCounter(String),
+    MissingKing(PieceColor),
+    TrailingField(String),
+    Invalid(Vec<Violation>),
+}
+
+impl std::fmt::Display for FenError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            FenError::MissingField(field) => write!(f, "Missing {} field", field),
+            FenError::RankCount(count) => write!(f, "Expected 10 ranks, found {}", count),
+            FenError::RankLength { rank, files } => {
+                write!(f, "Rank {} has {} files instead of 9", rank + 1, files)
+            }
+            FenError::BadPiece { offset, c } => {
+                write!(f, "Unknown piece {:?} at offset {}", c, offset)
+            }
+            FenError::BadTurn(turn) => write!(f, "Unknown side to move {:?}", turn),
+            FenError::BadCounter(counter) => write!(f, "Bad move counter {:?}", counter),
+            FenError::MissingKing(color) => write!(f, "No {:?} king on the board", color),
+            FenError::TrailingField(field) => write!(f, "Unexpected trailing field {:?}", field),
+            FenError::Invalid(violations) => write!(f, "Invalid position: {}", join(violations)),
+        }
+    }
+}
+
+impl std::error::Error for FenError {}
+
+fn fen_piece(c: char) -> Option<Piece> {
+    let color = if c.is_ascii_uppercase() {
+        PieceColor::Red
+    } else {
+        PieceColor::Black
+    };
+    let kind = match c.to_ascii_lowercase() {
+        'k' => PieceKind::King,
+        'a' => PieceKind::Advisor,
+        // Elephants and horses are also commonly written as E and H
+        'b' | 'e' => PieceKind::Bishop,
+        'n' | 'h' => PieceKind::Knight,
+        'r' => PieceKind::Rook,
+        'c' => PieceKind::Cannon,
+        'p' => PieceKind::Pawn,
+        _ => return None,
+    };
+    Some(Piece::new(kind, color))
+}
+
+fn fen_char(piece: Piece) -> char {
+    let c = char::from(piece.kind());
+    if piece.is_color(PieceColor::Red) {
+        c.to_ascii_uppercase()
+    } else {
+        c
+    }
+}
+
+impl Board {
+    pub fn from_fen(fen: &str) -> Result<Self, FenError> {
+        let mut fields = fen.split_whitespace();
+        let placement = fields.next().ok_or(FenError::MissingField("placement"))?;
+        let turn = match fields
+            .next()
+            .ok_or(FenError::MissingField("side to move"))?
+        {
+            "w" | "r" => PieceColor::Red,
+            "b" => PieceColor::Black,
+            turn => return Err(FenError::BadTurn(turn.to_string())),
+        };
+        // Castling and en passant never apply, but the fields are usually present
+        let _ = fields.next();
+        let _ = fields.next();
+        let mut counter = |default| {
+            fields.next().map_or(Ok(default), |counter: &str| {
+                counter
+                    .parse()
+                    .map_err(|_| FenError::BadCounter(counter.to_string()))
+            })
+        };
+        let halfmove = counter(0)?;
+        let fullmove = counter(1)?;
+        if let Some(field) = fields.next() {
+            return Err(FenError::TrailingField(field.to_string()));
+        }
+
+        let ranks: Vec<&str> = placement.split('/').collect();
+        if ranks.len() != RANKS {
+            return Err(FenError::RankCount(ranks.len()));
+        }
+        let mut content = [Piece::empty(); SQUARES];
+        let mut kings = [None; 2];
+        let mut offset = 0;
+        for (i, rank) in ranks.into_iter().enumerate() {
+            // FEN starts from Black's back rank
+            let mut row = Vec::with_capacity(FILES);
+            for (j, c) in rank.char_indices() {
+                if let Some(empty) = c.to_digit(10) {
+                    (0..empty).for_each(|_| row.push(Piece::empty()));
+                } else {
+                    let piece = fen_piece(c).ok_or(FenError::BadPiece {
+                        offset: offset + j,
+                        c,
+                    })?;
+                    if piece.is_kind(PieceKind::King) {
+                        let pos = Position::new(RANKS - 1 - i, row.len());
+                        kings[Into::<u8>::into(piece.color().unwrap()) as usize].get_or_insert(pos);
+                    }
+                    row.push(piece);
+                }
+                if row.len() > FILES {
+                    break;
+                }
+            }
+            if row.len() != FILES {
+                return Err(FenError::RankLength {
+                    rank: i,
+                    files: row.len(),
+                });
+            }
+            let start = (RANKS - 1 - i) * FILES;
+            content[start..start + FILES].copy_from_slice(&row);
+            offset += rank.len() + 1;
+        }
+        let kings = [
+            kings[0].ok_or(FenError::MissingKing(PieceColor::Red))?,
+            kings[1].ok_or(FenError::MissingKing(PieceColor::Black))?,
+        ];
+
+        let board = Board::from_parts(content, kings, turn, halfmove, fullmove);
+        board.validate().map_err(FenError::Invalid)?;
+        Ok(board)
+    }
+
+    pub fn to_fen(&self) -> String {
+        let mut result = String::new();
+        for rank in (0..RANKS).rev() {
+            let mut empty = 0;
+            for file in 0..FILES {
+                let piece = self.get(Position::new(rank, file));
+                if piece.is_empty() {
+                    empty += 1;
+                } else {
+                    if empty > 0 {
+                        result.push_str(&empty.to_string());
+                        empty = 0;
+                    }
+                    result.push(fen_char(piece));
+                }
+            }
+            if empty > 0 {
+                result.push_str(&empty.to_string());
+            }
+            if rank > 0 {
+                result.push('/');
+            }
+        }
+        let turn = match self.turn() {
+            PieceColor::Red => 'w',
+            PieceColor::Black => 'b',
+        };
+        result.push_str(&format!(
+            " {} - - {} {}",
+            turn, self.halfmove, self.fullmove
+        ));
+        result
+    }
+}
@@ -0,0 +1,66 @@
+use super::*;
+
+#[derive(Debug, Clone, Default)]
+pub struct GameHistory {
+    start: Board,
+    board: Board,
+    undos: Vec<Undo>,
+}
+
+impl GameHistory {
+    pub fn new(start: Board) -> Self {
+        Self {
+            board: start.clone(),
+            start,
+            undos: Vec::new(),
+        }
+    }
+
+    pub fn start(&self) -> &Board {
+        &self.start
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn len(&self) -> usize {
+        self.undos.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.undos.is_empty()
+    }
+
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        self.undos.iter().map(|undo| undo.mv)
+    }
+
+    pub fn undos(&self) -> &[Undo] {
+        &self.undos
+    }
+
+    /// Plays `mv` if it is legal in the current position.
+    pub fn play(&mut self, mv: Move) -> Result<&Undo, Error> {
+        self.board.check_move(mv)?;
+        let undo = self.board.make_move(mv);
+        self.undos.push(undo);
+        Ok(self.undos.last().unwrap())
+    }
+
+    /// Takes the last move back, returning it.
+    pub fn take_back(&mut self) -> Option<Move> {
+        let undo = self.undos.pop()?;
+        self.board.unmake_move(undo);
+        Some(undo.mv)
+    }
+
+    /// Every position of the game from the start, the current one included.
+    pub fn replay(&self) -> impl Iterator<Item = Board> + '_ {
+        let mut board = self.start.clone();
+        std::iter::once(board.clone()).chain(self.moves().map(move |mv| {
+            board.make_move(mv);
+            board.clone()
+        }))
+    }
+}
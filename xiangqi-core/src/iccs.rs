@@ -0,0 +1,98 @@
+use super::*;
+
+impl Position {
+    /// The same square seen from the other side of the board, which is how Black sees it.
+    pub fn flipped(self) -> Self {
+        Position::new_int(
+            RANKS as isize - 1 - self.rank_int(),
+            FILES as isize - 1 - self.file_int(),
+        )
+    }
+
+    /// The square in ICCS notation, with files `a` to `i` from Red's left and ranks `0` to `9`
+    /// from Red's side.
+    ///
+    /// ICCS coordinates never depend on who is looking at the board.
+    pub fn to_iccs(self) -> String {
+        format!(
+            "{}{}",
+            (b'a' + self.file() as u8) as char,
+            (b'0' + self.rank() as u8) as char
+        )
+    }
+
+    pub fn from_iccs(value: &str) -> Result<Self, Error> {
+        let mut chars = value.chars();
+        let pos = iccs_square(&mut chars)?;
+        if chars.next().is_some() {
+            return Err(Error::TrailingInput { offset: 2 });
+        }
+        Ok(pos)
+    }
+}
+
+fn iccs_square(chars: &mut impl Iterator<Item = char>) -> Result<Position, Error> {
+    let file = chars.next().ok_or(Error::UnexpectedEnd {
+        offset: 0,
+        expected: "file",
+    })?;
+    if !('a'..='i').contains(&file.to_ascii_lowercase()) {
+        return Err(Error::BadChar {
+            offset: 0,
+            c: file,
+            expected: "file",
+        });
+    }
+    let rank = chars.next().ok_or(Error::UnexpectedEnd {
+        offset: 1,
+        expected: "rank",
+    })?;
+    let rank = rank.to_digit(10).ok_or(Error::BadChar {
+        offset: 1,
+        c: rank,
+        expected: "rank",
+    })?;
+    Ok(Position::new(
+        rank as usize,
+        (file.to_ascii_lowercase() as u8 - b'a') as usize,
+    ))
+}
+
+impl Move {
+    pub fn flipped(self) -> Self {
+        Move::new(self.from.flipped(), self.to.flipped())
+    }
+
+    /// The move in ICCS notation, such as `h2e2`.
+    pub fn to_iccs(self) -> String {
+        format!("{}{}", self.from.to_iccs(), self.to.to_iccs())
+    }
+
+    /// Reads `h2e2`, also accepting the upper case `H2-E2` form.
+    pub fn from_iccs(value: &str) -> Result<Self, Error> {
+        let mut chars = value.trim().chars().peekable();
+        let from = iccs_square(&mut chars)?;
+        let mut offset = 2;
+        if chars.next_if_eq(&'-').is_some() {
+            offset += 1;
+        }
+        let to = iccs_square(&mut chars).map_err(|err| err.at(offset))?;
+        if chars.next().is_some() {
+            return Err(Error::TrailingInput { offset: offset + 2 });
+        }
+        Ok(Move::new(from, to))
+    }
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.to_iccs())
+    }
+}
+
+impl std::str::FromStr for Move {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Self, Error> {
+        Move::from_iccs(s)
+    }
+}
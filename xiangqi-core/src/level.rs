@@ -0,0 +1,155 @@
+use super::*;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How strongly the computer plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Level {
+    Beginner,
+    Novice,
+    #[default]
+    Intermediate,
+    Advanced,
+    Master,
+}
+
+impl Level {
+    pub const ALL: [Level; 5] = [
+        Level::Beginner,
+        Level::Novice,
+        Level::Intermediate,
+        Level::Advanced,
+        Level::Master,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Level::Beginner => "Beginner",
+            Level::Novice => "Novice",
+            Level::Intermediate => "Intermediate",
+            Level::Advanced => "Advanced",
+            Level::Master => "Master",
+        }
+    }
+
+    pub fn limits(self) -> SearchLimits {
+        let (depth, nodes, time) = match self {
+            Level::Beginner => (1, Some(2_000), None),
+            Level::Novice => (2, Some(20_000), None),
+            Level::Intermediate => (4, Some(200_000), Some(1_000)),
+            Level::Advanced => (MAX_DEPTH, None, Some(2_000)),
+            Level::Master => (MAX_DEPTH, None, Some(5_000)),
+        };
+        SearchLimits {
+            depth,
+            nodes,
+            time: time.map(Duration::from_millis),
+        }
+    }
+
+    /// The most the evaluation of a position is randomly off by.
+    pub fn noise(self) -> i32 {
+        match self {
+            Level::Beginner => 150,
+            Level::Novice => 80,
+            Level::Intermediate => 30,
+            Level::Advanced | Level::Master => 0,
+        }
+    }
+
+    /// The chance in percent of playing any move at all instead of searching.
+    pub fn blunder_chance(self) -> u64 {
+        match self {
+            Level::Beginner => 25,
+            Level::Novice => 10,
+            Level::Intermediate => 3,
+            Level::Advanced | Level::Master => 0,
+        }
+    }
+
+    /// Picks a move for `board` the way a player of this level would.
+    pub fn choose(
+        self,
+        searcher: &mut Searcher,
+        board: &Board,
+        report: impl FnMut(&SearchInfo),
+    ) -> Option<Move> {
+        if random() % 100 < self.blunder_chance() {
+            let mut moves = MoveList::new();
+            board.legal_moves_into(&mut moves);
+            if !moves.is_empty() {
+                return Some(moves[random() as usize % moves.len()]);
+            }
+        }
+        searcher.set_noise(self.noise());
+        searcher.search(board, self.limits(), report)
+    }
+}
+
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// What the computer cares about, on top of material.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum PlayStyle {
+    #[default]
+    Balanced,
+    /// Pushes pawns over the river and goes for active pieces, leaving its own king exposed.
+    Aggressive,
+    /// Keeps its guards and closes the files to its palace.
+    Defensive,
+}
+
+impl PlayStyle {
+    pub const ALL: [PlayStyle; 3] = [
+        PlayStyle::Balanced,
+        PlayStyle::Aggressive,
+        PlayStyle::Defensive,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            PlayStyle::Balanced => "Balanced",
+            PlayStyle::Aggressive => "Aggressive",
+            PlayStyle::Defensive => "Defensive",
+        }
+    }
+
+    pub fn weights(self) -> Weights {
+        let mut weights = Weights::default();
+        match self {
+            PlayStyle::Balanced => {}
+            PlayStyle::Aggressive => {
+                weights.mobility = [0, 0, 2, 0, 0, 0, 6, 3];
+                weights.river_pawn = 35;
+                weights.missing_advisor = 15;
+                weights.missing_bishop = 10;
+                weights.open_file = 20;
+            }
+            PlayStyle::Defensive => {
+                weights.mobility = [0, 0, 1, 0, 0, 0, 3, 1];
+                weights.river_pawn = 10;
+                weights.missing_advisor = 45;
+                weights.missing_bishop = 35;
+                weights.open_file = 50;
+            }
+        }
+        weights
+    }
+}
+
+impl std::fmt::Display for PlayStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// A number that is different in every call, good enough to vary play.
+pub(crate) fn random() -> u64 {
+    // Every new state is keyed differently, so hashing nothing still gives a fresh number
+    RandomState::new().build_hasher().finish()
+}
@@ -0,0 +1,46 @@
+mod board;
+mod book;
+mod error;
+mod eval;
+mod fen;
+mod history;
+mod iccs;
+mod level;
+mod moves;
+mod notation;
+mod outcome;
+mod perft;
+mod pgn;
+mod pieces;
+mod rules;
+mod search;
+mod ucci;
+mod validate;
+mod xqf;
+mod zobrist;
+
+pub use board::*;
+pub use book::*;
+pub use error::*;
+pub use eval::*;
+pub use fen::*;
+pub use history::*;
+pub use level::*;
+pub use moves::*;
+pub use outcome::*;
+pub use pgn::*;
+pub use pieces::*;
+pub use rules::*;
+pub use search::*;
+pub use ucci::*;
+pub use validate::*;
+pub use xqf::*;
+pub(crate) use zobrist::*;
+
+pub(crate) use num_enum::IntoPrimitive;
+pub(crate) use std::collections::HashSet;
+
+pub const FILES: usize = 9;
+pub const RANKS: usize = 10;
+pub static MOVE_DIRS: [MoveDir; 4] = [MoveDir::Left, MoveDir::Right, MoveDir::Up, MoveDir::Down];
+pub static DIAG_DIRS: [DiagDir; 4] = [DiagDir::LU, DiagDir::LD, DiagDir::RU, DiagDir::RD];
@@ -0,0 +1,105 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Move {
+    pub from: Position,
+    pub to: Position,
+}
+
+impl Move {
+    pub const fn new(from: Position, to: Position) -> Self {
+        Self { from, to }
+    }
+}
+
+/// Everything [`Board::unmake_move`] needs to take a move back.
+#[derive(Debug, Clone, Copy)]
+pub struct Undo {
+    pub mv: Move,
+    pub captured: Piece,
+    pub turn: PieceColor,
+    pub kings: [Position; 2],
+    pub halfmove: u32,
+    pub fullmove: u32,
+    pub zobrist: u64,
+}
+
+impl Board {
+    pub(crate) fn is_safe(&self, mv: Move) -> bool {
+        let mut board = self.clone();
+        board.force(mv.from, mv.to);
+        !board.is_check()
+    }
+
+    /// Appends all moves of the side to move that do not leave its own king in check.
+    pub fn legal_moves_into(&self, result: &mut MoveList) {
+        self.pseudo_moves(result);
+        result.retain(|mv| self.is_safe(mv));
+    }
+
+    pub fn legal_moves(&self) -> Vec<Move> {
+        let mut result = MoveList::new();
+        self.legal_moves_into(&mut result);
+        result.to_vec()
+    }
+
+    /// Tells why `mv` cannot be played in this position, if it cannot.
+    pub fn check_move(&self, mv: Move) -> Result<(), Error> {
+        let illegal = |reason| Err(Error::IllegalMove { mv, reason });
+        if mv.from.legal().is_none() || mv.to.legal().is_none() {
+            return illegal(IllegalReason::OffBoard);
+        }
+        let piece = self.get(mv.from);
+        if piece.is_empty() {
+            return illegal(IllegalReason::EmptySquare);
+        }
+        if !piece.is_color(self.turn()) {
+            return Err(Error::WrongSide {
+                mv,
+                turn: self.turn(),
+            });
+        }
+        let mut reachable = MoveList::new();
+        self.reachable_into(mv.from, &mut reachable);
+        if !reachable.contains(&mv) {
+            return illegal(IllegalReason::Unreachable);
+        }
+        if !self.is_safe(mv) {
+            return illegal(IllegalReason::KingInCheck);
+        }
+        Ok(())
+    }
+
+    pub fn is_legal(&self, mv: Move) -> bool {
+        self.check_move(mv).is_ok()
+    }
+}
+
+impl Board {
+    /// Plays `mv` without checking it, passing the turn to the opponent.
+    pub fn make_move(&mut self, mv: Move) -> Undo {
+        let undo = Undo {
+            mv,
+            captured: self.get(mv.to),
+            turn: self.turn,
+            kings: self.kings,
+            halfmove: self.halfmove,
+            fullmove: self.fullmove,
+            zobrist: self.zobrist,
+        };
+        self.force(mv.from, mv.to);
+        self.next_turn();
+        undo
+    }
+
+    /// Restores the board to exactly how it was before `undo` was made.
+    pub fn unmake_move(&mut self, undo: Undo) {
+        let piece = self.set(undo.mv.to, undo.captured);
+        self.set(undo.mv.from, piece);
+        self.kings = undo.kings;
+        self.turn = undo.turn;
+        self.halfmove = undo.halfmove;
+        self.fullmove = undo.fullmove;
+        self.zobrist = undo.zobrist;
+    }
+}
@@ -0,0 +1,270 @@
+use super::*;
+
+static RED_NUMERALS: [char; 9] = ['一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Marker {
+    /// The file the piece stands on.
+    File(u8),
+    /// One of `count` pieces of the same kind on a file, counted from the front. Pawns stacked
+    /// on several files also name the file.
+    Tandem {
+        index: usize,
+        count: usize,
+        file: Option<u8>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Forward,
+    Backward,
+    Sideways,
+}
+
+/// A move broken down the way both WXF and Chinese notation describe it.
+struct Description {
+    piece: Piece,
+    marker: Marker,
+    op: Op,
+    /// The destination file, or the number of ranks moved for straight forward or backward moves.
+    dest: u8,
+}
+
+/// Files are numbered 1 to 9 from each player's right.
+fn file_number(file: usize, color: PieceColor) -> u8 {
+    match color {
+        PieceColor::Red => (FILES - file) as u8,
+        PieceColor::Black => file as u8 + 1,
+    }
+}
+
+fn ahead(rank: usize, color: PieceColor) -> isize {
+    match color {
+        PieceColor::Red => rank as isize,
+        PieceColor::Black => -(rank as isize),
+    }
+}
+
+impl Board {
+    /// Ranks on `file` holding `piece`, from the front as seen by its owner.
+    fn stack(&self, file: usize, piece: Piece, color: PieceColor) -> Vec<usize> {
+        let mut ranks: Vec<usize> = (0..RANKS)
+            .filter(|rank| self.get(Position::new(*rank, file)) == piece)
+            .collect();
+        ranks.sort_by_key(|rank| -ahead(*rank, color));
+        ranks
+    }
+
+    fn describe(&self, mv: Move) -> Description {
+        let piece = self.get(mv.from);
+        let color = piece.color().unwrap_or(self.turn());
+        let file = file_number(mv.from.file(), color);
+
+        let stack = self.stack(mv.from.file(), piece, color);
+        // Advisors and bishops are told apart by the direction they move in
+        let marker = if stack.len() < 2
+            || piece.is_kind(PieceKind::Advisor)
+            || piece.is_kind(PieceKind::Bishop)
+        {
+            Marker::File(file)
+        } else {
+            let stacked_files = (0..FILES)
+                .filter(|file| self.stack(*file, piece, color).len() >= 2)
+                .count();
+            Marker::Tandem {
+                index: stack
+                    .iter()
+                    .position(|rank| *rank == mv.from.rank())
+                    .unwrap(),
+                count: stack.len(),
+                file: (stacked_files > 1).then_some(file),
+            }
+        };
+
+        let advance = ahead(mv.to.rank(), color) - ahead(mv.from.rank(), color);
+        let op = match advance {
+            0 => Op::Sideways,
+            1.. => Op::Forward,
+            _ => Op::Backward,
+        };
+        let diagonal = matches!(
+            piece.kind(),
+            PieceKind::Knight | PieceKind::Bishop | PieceKind::Advisor
+        );
+        let dest = if op == Op::Sideways || diagonal {
+            file_number(mv.to.file(), color)
+        } else {
+            advance.unsigned_abs() as u8
+        };
+
+        Description {
+            piece,
+            marker,
+            op,
+            dest,
+        }
+    }
+
+    /// The move in WXF notation, such as `C2=5`.
+    ///
+    /// Of two pieces on a file, the front one is marked `+` and the rear one `-`, as in `C+=5`.
+    /// Three or more pawns on a file are marked `a` to `e` from the front, and the file follows
+    /// the marker when pawns are stacked on more than one file.
+    pub fn to_wxf(&self, mv: Move) -> String {
+        let description = self.describe(mv);
+        let mut result = String::new();
+        result.push(match description.piece.kind() {
+            PieceKind::Empty => '?',
+            PieceKind::Pawn => 'P',
+            PieceKind::Cannon => 'C',
+            PieceKind::King => 'K',
+            PieceKind::Advisor => 'A',
+            PieceKind::Bishop => 'E',
+            PieceKind::Knight => 'H',
+            PieceKind::Rook => 'R',
+        });
+        match description.marker {
+            Marker::File(file) => result.push((b'0' + file) as char),
+            Marker::Tandem { index, count, file } => {
+                result.push(match (index, count) {
+                    (0, 2) => '+',
+                    (_, 2) => '-',
+                    _ => (b'a' + index as u8) as char,
+                });
+                if let Some(file) = file {
+                    result.push((b'0' + file) as char);
+                }
+            }
+        }
+        result.push(match description.op {
+            Op::Forward => '+',
+            Op::Backward => '-',
+            Op::Sideways => '=',
+        });
+        result.push((b'0' + description.dest) as char);
+        result
+    }
+
+    /// The move in traditional Chinese notation, such as `炮二平五` or `马8进7`.
+    ///
+    /// Red files are written in Chinese numerals and Black files in Arabic numerals. Stacked
+    /// pieces are told apart by 前, 中 and 后, or by 一 to 五 for four or more pawns.
+    pub fn to_chinese(&self, mv: Move) -> String {
+        let description = self.describe(mv);
+        let color = description.piece.color().unwrap_or(self.turn());
+        let number = |n: u8| match color {
+            PieceColor::Red => RED_NUMERALS[n as usize - 1],
+            PieceColor::Black => (b'0' + n) as char,
+        };
+        let name = match (description.piece.kind(), color) {
+            (PieceKind::Empty, _) => '?',
+            (PieceKind::Pawn, PieceColor::Red) => '兵',
+            (PieceKind::Pawn, PieceColor::Black) => '卒',
+            (PieceKind::Cannon, _) => '炮',
+            (PieceKind::King, PieceColor::Red) => '帅',
+            (PieceKind::King, PieceColor::Black) => '将',
+            (PieceKind::Advisor, PieceColor::Red) => '仕',
+            (PieceKind::Advisor, PieceColor::Black) => '士',
+            (PieceKind::Bishop, PieceColor::Red) => '相',
+            (PieceKind::Bishop, PieceColor::Black) => '象',
+            (PieceKind::Knight, _) => '马',
+            (PieceKind::Rook, _) => '车',
+        };
+
+        let mut result = String::new();
+        match description.marker {
+            Marker::File(file) => {
+                result.push(name);
+                result.push(number(file));
+            }
+            Marker::Tandem { index, count, file } => {
+                result.push(match (index, count) {
+                    (0, 2 | 3) => '前',
+                    (1, 3) => '中',
+                    (_, 2 | 3) => '后',
+                    _ => RED_NUMERALS[index],
+                });
+                result.push(file.map(number).unwrap_or(name));
+            }
+        }
+        result.push(match description.op {
+            Op::Forward => '进',
+            Op::Backward => '退',
+            Op::Sideways => '平',
+        });
+        result.push(number(description.dest));
+        result
+    }
+
+    fn find_move(
+        &self,
+        notation: &str,
+        to_string: impl Fn(&Board, Move) -> String,
+    ) -> Result<Move, Error> {
+        self.legal_moves()
+            .into_iter()
+            .find(|mv| to_string(self, *mv) == notation)
+            .ok_or_else(|| Error::UnknownMove(notation.to_string()))
+    }
+
+    /// Reads a move in WXF notation for the side to move.
+    ///
+    /// The `B` and `N` piece letters, `.` for sideways moves and tandem markers written before
+    /// the piece letter (`+C.5`) are accepted as well.
+    pub fn from_wxf(&self, notation: &str) -> Result<Move, Error> {
+        let mut chars: Vec<char> = notation.trim().chars().collect();
+        if chars.len() > 1 && matches!(chars[0], '+' | '-') && chars[1].is_ascii_alphabetic() {
+            chars.swap(0, 1);
+        }
+        let mut normalized = String::new();
+        for (i, c) in chars.into_iter().enumerate() {
+            normalized.push(match c {
+                _ if i == 0 => match c.to_ascii_uppercase() {
+                    'B' => 'E',
+                    'N' => 'H',
+                    c => c,
+                },
+                '.' => '=',
+                c => c.to_ascii_lowercase(),
+            });
+        }
+        self.find_move(&normalized, Board::to_wxf)
+    }
+
+    /// Reads a move in Chinese notation for the side to move.
+    ///
+    /// Traditional characters and either kind of numerals are accepted for both sides.
+    pub fn from_chinese(&self, notation: &str) -> Result<Move, Error> {
+        let notation = normalize_chinese(notation.trim());
+        self.find_move(&notation, |board, mv| {
+            normalize_chinese(&board.to_chinese(mv))
+        })
+    }
+}
+
+/// Maps every way of writing a piece, number or move to one character.
+fn normalize_chinese(notation: &str) -> String {
+    notation
+        .chars()
+        .map(|c| match c {
+            '帅' | '帥' | '将' | '將' => 'K',
+            '仕' | '士' => 'A',
+            '相' | '象' => 'B',
+            '马' | '馬' | '傌' => 'N',
+            '车' | '車' | '俥' => 'R',
+            '炮' | '砲' | '包' => 'C',
+            '兵' | '卒' => 'P',
+            '进' | '進' => '+',
+            '退' => '-',
+            '平' => '=',
+            '後' => '后',
+            '１'..='９' => char::from_u32(c as u32 - '１' as u32 + '1' as u32).unwrap(),
+            c => RED_NUMERALS
+                .iter()
+                .position(|numeral| *numeral == c)
+                .map(|n| (b'1' + n as u8) as char)
+                .unwrap_or(c),
+        })
+        .collect()
+}
@@ -0,0 +1,155 @@
+use super::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum WinReason {
+    Checkmate,
+    /// The side to move has no legal move while not in check, which loses in Xiangqi.
+    Stalemate,
+    /// The opponent kept checking or chasing in a repeated position.
+    Repetition,
+    Resignation,
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DrawReason {
+    /// No capture within [`GameRules::move_limit`].
+    MoveLimit,
+    /// Neither side has a piece that could ever give mate.
+    InsufficientMaterial,
+    Repetition,
+    Agreement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum GameOutcome {
+    RedWins(WinReason),
+    BlackWins(WinReason),
+    Draw(DrawReason),
+}
+
+impl GameOutcome {
+    pub fn wins(color: PieceColor, reason: WinReason) -> Self {
+        match color {
+            PieceColor::Red => GameOutcome::RedWins(reason),
+            PieceColor::Black => GameOutcome::BlackWins(reason),
+        }
+    }
+
+    /// The winner, or `None` for a draw.
+    pub fn winner(self) -> Option<PieceColor> {
+        match self {
+            GameOutcome::RedWins(_) => Some(PieceColor::Red),
+            GameOutcome::BlackWins(_) => Some(PieceColor::Black),
+            GameOutcome::Draw(_) => None,
+        }
+    }
+}
+
+impl std::fmt::Display for WinReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            WinReason::Checkmate => write!(f, "checkmate"),
+            WinReason::Stalemate => write!(f, "stalemate"),
+            WinReason::Repetition => write!(f, "repetition"),
+            WinReason::Resignation => write!(f, "resignation"),
+            WinReason::Timeout => write!(f, "timeout"),
+        }
+    }
+}
+
+impl std::fmt::Display for DrawReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            DrawReason::MoveLimit => write!(f, "move limit"),
+            DrawReason::InsufficientMaterial => write!(f, "insufficient material"),
+            DrawReason::Repetition => write!(f, "repetition"),
+            DrawReason::Agreement => write!(f, "agreement"),
+        }
+    }
+}
+
+impl std::fmt::Display for GameOutcome {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            GameOutcome::RedWins(reason) => write!(f, "Red wins by {}", reason),
+            GameOutcome::BlackWins(reason) => write!(f, "Black wins by {}", reason),
+            GameOutcome::Draw(reason) => write!(f, "Draw by {}", reason),
+        }
+    }
+}
+
+/// The rules that can end a game without a mate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GameRules {
+    /// Plies without a capture after which the game is drawn, if any.
+    pub move_limit: Option<u32>,
+    pub repetition: RuleSet,
+}
+
+impl Default for GameRules {
+    /// The 60-move rule, counted as 120 plies, with the Asian repetition rules.
+    fn default() -> Self {
+        Self {
+            move_limit: Some(120),
+            repetition: RuleSet::default(),
+        }
+    }
+}
+
+impl Board {
+    /// Whether neither side has a rook, knight, cannon or pawn left to attack with.
+    pub fn is_insufficient_material(&self) -> bool {
+        self.content.iter().all(|piece| {
+            matches!(
+                piece.kind(),
+                PieceKind::Empty | PieceKind::King | PieceKind::Advisor | PieceKind::Bishop
+            )
+        })
+    }
+
+    /// How the game ends in this position, ignoring how it was reached.
+    pub fn outcome(&self, rules: &GameRules) -> Option<GameOutcome> {
+        let mut moves = MoveList::new();
+        self.legal_moves_into(&mut moves);
+        if moves.is_empty() {
+            let reason = if self.is_check() {
+                WinReason::Checkmate
+            } else {
+                WinReason::Stalemate
+            };
+            Some(GameOutcome::wins(self.turn().opposite(), reason))
+        } else if rules
+            .move_limit
+            .is_some_and(|limit| self.halfmove() >= limit)
+        {
+            Some(GameOutcome::Draw(DrawReason::MoveLimit))
+        } else if self.is_insufficient_material() {
+            Some(GameOutcome::Draw(DrawReason::InsufficientMaterial))
+        } else {
+            None
+        }
+    }
+}
+
+impl GameHistory {
+    /// How the game ends after the moves played so far, repetitions included.
+    pub fn outcome(&self, rules: &GameRules) -> Option<GameOutcome> {
+        let board = self.board();
+        let outcome = board.outcome(rules);
+        // A mate on the move that repeats the position still counts as a mate
+        if outcome.is_some_and(|outcome| outcome.winner().is_some()) {
+            return outcome;
+        }
+        match self.repetition(rules.repetition).map(|rep| rep.verdict()) {
+            Some(Verdict::Loses(color)) => {
+                Some(GameOutcome::wins(color.opposite(), WinReason::Repetition))
+            }
+            Some(Verdict::Draw) => Some(GameOutcome::Draw(DrawReason::Repetition)),
+            None => outcome,
+        }
+    }
+}
@@ -0,0 +1,46 @@
+use super::*;
+
+impl Board {
+    fn perft_mut(&mut self, depth: usize) -> u64 {
+        if depth == 0 {
+            return 1;
+        }
+        let mut moves = MoveList::new();
+        self.legal_moves_into(&mut moves);
+        if depth == 1 {
+            return moves.len() as u64;
+        }
+        let mut result = 0;
+        for &mv in moves.iter() {
+            let undo = self.make_move(mv);
+            result += self.perft_mut(depth - 1);
+            self.unmake_move(undo);
+        }
+        result
+    }
+
+    /// Counts the leaf nodes of the legal move tree `depth` plies deep.
+    pub fn perft(&self, depth: usize) -> u64 {
+        self.clone().perft_mut(depth)
+    }
+
+    /// Same as [`Board::perft`], broken down per root move.
+    pub fn perft_divide(&self, depth: usize) -> Vec<(Move, u64)> {
+        if depth == 0 {
+            return Vec::new();
+        }
+        let mut board = self.clone();
+        let mut result: Vec<_> = self
+            .legal_moves()
+            .into_iter()
+            .map(|mv| {
+                let undo = board.make_move(mv);
+                let nodes = board.perft_mut(depth - 1);
+                board.unmake_move(undo);
+                (mv, nodes)
+            })
+            .collect();
+        result.sort_by_key(|(mv, _)| (String::from(mv.from), String::from(mv.to)));
+        result
+    }
+}
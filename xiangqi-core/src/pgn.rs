@@ -0,0 +1,314 @@
+use super::*;
+
+/// How moves are written in a game record.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum MoveFormat {
+    #[default]
+    Iccs,
+    Wxf,
+    Chinese,
+}
+
+impl MoveFormat {
+    /// The value of the `Format` tag.
+    pub fn name(self) -> &'static str {
+        match self {
+            MoveFormat::Iccs => "ICCS",
+            MoveFormat::Wxf => "WXF",
+            MoveFormat::Chinese => "Chinese",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_uppercase().as_str() {
+            "ICCS" => Some(MoveFormat::Iccs),
+            "WXF" => Some(MoveFormat::Wxf),
+            "CHINESE" => Some(MoveFormat::Chinese),
+            _ => None,
+        }
+    }
+
+    fn write(self, board: &Board, mv: Move) -> String {
+        match self {
+            MoveFormat::Iccs => mv.to_iccs(),
+            MoveFormat::Wxf => board.to_wxf(mv),
+            MoveFormat::Chinese => board.to_chinese(mv),
+        }
+    }
+
+    fn read(self, board: &Board, text: &str) -> Result<Move, Error> {
+        match self {
+            MoveFormat::Iccs => Move::from_iccs(text),
+            MoveFormat::Wxf => board.from_wxf(text),
+            MoveFormat::Chinese => board.from_chinese(text),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PgnError {
+    /// A tag line that is not `[Name "value"]`.
+    BadTag(String),
+    Fen(FenError),
+    /// A move that could not be read or is illegal, with its ply counted from the start.
+    BadMove {
+        ply: usize,
+        text: String,
+    },
+    /// A comment or variation that is never closed.
+    Unterminated(char),
+    /// A closing bracket that was never opened.
+    Unmatched(char),
+}
+
+impl std::fmt::Display for PgnError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            PgnError::BadTag(line) => write!(f, "Bad tag {:?}", line),
+            PgnError::Fen(err) => write!(f, "Bad FEN tag: {}", err),
+            PgnError::BadMove { ply, text } => write!(f, "Bad move {:?} at ply {}", text, ply),
+            PgnError::Unterminated(c) => write!(f, "Missing closing {:?}", c),
+            PgnError::Unmatched(c) => write!(f, "Unmatched {:?}", c),
+        }
+    }
+}
+
+impl std::error::Error for PgnError {}
+
+impl From<FenError> for PgnError {
+    fn from(value: FenError) -> Self {
+        PgnError::Fen(value)
+    }
+}
+
+/// The PGN result token for an outcome, `*` while the game goes on.
+pub fn pgn_result(outcome: Option<GameOutcome>) -> &'static str {
+    match outcome {
+        Some(GameOutcome::RedWins(_)) => "1-0",
+        Some(GameOutcome::BlackWins(_)) => "0-1",
+        Some(GameOutcome::Draw(_)) => "1/2-1/2",
+        None => "*",
+    }
+}
+
+static RESULTS: [&str; 4] = ["1-0", "0-1", "1/2-1/2", "*"];
+
+/// A complete game: its header tags and the moves played from its starting position.
+#[derive(Debug, Clone, Default)]
+pub struct GameRecord {
+    pub tags: Vec<(String, String)>,
+    pub history: GameHistory,
+}
+
+impl GameRecord {
+    pub fn new(history: GameHistory) -> Self {
+        Self {
+            tags: Vec::new(),
+            history,
+        }
+    }
+
+    pub fn tag(&self, name: &str) -> Option<&str> {
+        self.tags
+            .iter()
+            .find(|(tag, _)| tag == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn set_tag(&mut self, name: &str, value: impl Into<String>) {
+        let value = value.into();
+        match self.tags.iter_mut().find(|(tag, _)| tag == name) {
+            Some((_, old)) => *old = value,
+            None => self.tags.push((name.to_string(), value)),
+        }
+    }
+
+    /// Reads a game in the xiangqi PGN dialect.
+    ///
+    /// The `FEN` tag sets the starting position. Moves are read in the notation named by the
+    /// `Format` tag, or else in whichever of ICCS, WXF and Chinese notation fits. Comments,
+    /// variations and annotation glyphs are skipped.
+    pub fn from_pgn(pgn: &str) -> Result<Self, PgnError> {
+        let mut tags = Vec::new();
+        let mut movetext = String::new();
+        for line in pgn.lines() {
+            let line = line.trim();
+            if line.starts_with('[') && movetext.trim().is_empty() {
+                tags.push(parse_tag(line).ok_or_else(|| PgnError::BadTag(line.to_string()))?);
+            } else {
+                movetext.push_str(line);
+                movetext.push('\n');
+            }
+        }
+
+        let start = tags
+            .iter()
+            .find(|(tag, _)| tag == "FEN")
+            .map(|(_, fen)| Board::from_fen(fen))
+            .transpose()?
+            .unwrap_or_default();
+        let format = tags
+            .iter()
+            .find(|(tag, _)| tag == "Format")
+            .and_then(|(_, name)| MoveFormat::from_name(name));
+
+        let mut history = GameHistory::new(start);
+        for token in tokens(&movetext)? {
+            // Move numbers may be glued to the move, as in `1.h2e2`
+            let text = token.trim_start_matches(|c: char| c.is_ascii_digit() || c == '.');
+            if text.is_empty() || RESULTS.contains(&token) || token.starts_with('$') {
+                continue;
+            }
+            let board = history.board();
+            let formats = match format {
+                Some(format) => vec![format],
+                None => vec![MoveFormat::Iccs, MoveFormat::Wxf, MoveFormat::Chinese],
+            };
+            let mv = formats
+                .into_iter()
+                .filter_map(|format| format.read(board, text).ok())
+                .find(|mv| board.is_legal(*mv))
+                .ok_or_else(|| PgnError::BadMove {
+                    ply: history.len(),
+                    text: text.to_string(),
+                })?;
+            history.play(mv).unwrap();
+        }
+
+        Ok(Self { tags, history })
+    }
+
+    /// Reads every game of a file that holds several, one after another.
+    pub fn read_all(pgn: &str) -> Vec<Result<Self, PgnError>> {
+        let mut games = Vec::new();
+        let mut game = String::new();
+        let mut in_moves = false;
+        for line in pgn.lines() {
+            let tag = line.trim_start().starts_with('[');
+            // Tags after the moves start the next game
+            if tag && in_moves {
+                games.push(Self::from_pgn(&game));
+                game.clear();
+                in_moves = false;
+            }
+            in_moves |= !tag && !line.trim().is_empty();
+            game.push_str(line);
+            game.push('\n');
+        }
+        if in_moves {
+            games.push(Self::from_pgn(&game));
+        }
+        games
+    }
+
+    /// Writes the game with its moves in `format`, adding the `FEN` tag when it does not start
+    /// from the usual position.
+    pub fn to_pgn(&self, format: MoveFormat) -> String {
+        let mut record = self.clone();
+        if record.tag("Game").is_none() {
+            record
+                .tags
+                .insert(0, ("Game".to_string(), "Chinese Chess".to_string()));
+        }
+        let start = self.history.start();
+        if *start != Board::default() || start.fullmove() != 1 {
+            record.set_tag("FEN", start.to_fen());
+        }
+        record.set_tag("Format", format.name());
+
+        let mut out = String::new();
+        for (name, value) in record.tags.iter() {
+            let value = value.replace('\\', "\\\\").replace('"', "\\\"");
+            out.push_str(&format!("[{} \"{}\"]\n", name, value));
+        }
+        out.push('\n');
+
+        let mut tokens = Vec::new();
+        let mut board = start.clone();
+        for (i, mv) in self.history.moves().enumerate() {
+            let text = format.write(&board, mv);
+            tokens.push(match board.turn() {
+                PieceColor::Red => format!("{}. {}", board.fullmove(), text),
+                PieceColor::Black if i == 0 => format!("{}... {}", board.fullmove(), text),
+                PieceColor::Black => text,
+            });
+            board.make_move(mv);
+        }
+        tokens.push(self.tag("Result").unwrap_or("*").to_string());
+
+        let mut line = String::new();
+        for token in tokens {
+            if !line.is_empty() && line.chars().count() + token.chars().count() >= 80 {
+                out.push_str(&line);
+                out.push('\n');
+                line.clear();
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(&token);
+        }
+        out.push_str(&line);
+        out.push('\n');
+        out
+    }
+}
+
+fn parse_tag(line: &str) -> Option<(String, String)> {
+    let inner = line.strip_prefix('[')?.strip_suffix(']')?.trim();
+    let (name, value) = inner.split_once(char::is_whitespace)?;
+    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+    let mut unescaped = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.push(chars.next()?),
+            c => unescaped.push(c),
+        }
+    }
+    Some((name.to_string(), unescaped))
+}
+
+/// Splits the movetext into tokens, leaving out comments and variations.
+fn tokens(movetext: &str) -> Result<Vec<&str>, PgnError> {
+    let mut result = Vec::new();
+    let mut depth = 0;
+    let mut rest = movetext;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '{' => {
+                let end = rest.find('}').ok_or(PgnError::Unterminated('}'))?;
+                rest = &rest[end + 1..];
+            }
+            ';' => {
+                let end = rest.find('\n').unwrap_or(rest.len());
+                rest = &rest[end..];
+            }
+            '(' => {
+                depth += 1;
+                rest = &rest[1..];
+            }
+            ')' => {
+                if depth == 0 {
+                    return Err(PgnError::Unmatched(')'));
+                }
+                depth -= 1;
+                rest = &rest[1..];
+            }
+            c if c.is_whitespace() => rest = &rest[c.len_utf8()..],
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || "{;()".contains(c))
+                    .unwrap_or(rest.len());
+                if depth == 0 {
+                    result.push(&rest[..end]);
+                }
+                rest = &rest[end..];
+            }
+        }
+    }
+    if depth > 0 {
+        return Err(PgnError::Unterminated(')'));
+    }
+    Ok(result)
+}
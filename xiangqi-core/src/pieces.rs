@@ -25,7 +25,11 @@ impl TryFrom<char> for PieceKind {
             'b' => Ok(PieceKind::Bishop),
             'n' => Ok(PieceKind::Knight),
             'r' => Ok(PieceKind::Rook),
-            _ => Err(Error),
+            c => Err(Error::BadChar {
+                offset: 0,
+                c,
+                expected: "piece kind",
+            }),
         }
     }
 }
@@ -67,7 +71,11 @@ impl TryFrom<char> for PieceColor {
         match value {
             'r' => Ok(PieceColor::Red),
             'b' => Ok(PieceColor::Black),
-            _ => Err(Error),
+            c => Err(Error::BadChar {
+                offset: 0,
+                c,
+                expected: "color",
+            }),
         }
     }
 }
@@ -100,7 +108,7 @@ impl From<PieceColor> for bool {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct Piece {
     kind: PieceKind,
     color: PieceColor,
@@ -108,7 +116,12 @@ pub struct Piece {
 
 impl Piece {
     pub fn new(kind: PieceKind, color: PieceColor) -> Self {
-        Self { kind, color }
+        if kind == PieceKind::Empty {
+            // Empty squares have no color, so they all compare equal
+            Self::empty()
+        } else {
+            Self { kind, color }
+        }
     }
 
     pub fn empty() -> Self {
@@ -165,9 +178,25 @@ impl TryFrom<&str> for Piece {
     type Error = Error;
     fn try_from(value: &str) -> Result<Self, Error> {
         let mut chars = value.chars();
-        let kind = chars.next().ok_or(Error)?.try_into()?;
-        let color = chars.next().ok_or(Error)?.try_into()?;
-        Ok(Piece { kind, color })
+        let kind = chars
+            .next()
+            .ok_or(Error::UnexpectedEnd {
+                offset: 0,
+                expected: "piece kind",
+            })?
+            .try_into()?;
+        let color = chars
+            .next()
+            .ok_or(Error::UnexpectedEnd {
+                offset: 1,
+                expected: "color",
+            })?
+            .try_into()
+            .map_err(|err: Error| err.at(1))?;
+        if chars.next().is_some() {
+            return Err(Error::TrailingInput { offset: 2 });
+        }
+        Ok(Piece::new(kind, color))
     }
 }
 
@@ -177,7 +206,8 @@ impl From<Piece> for String {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "bevy", derive(bevy::prelude::Component))]
 pub struct Position(isize, isize);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -239,6 +269,14 @@ impl Position {
     pub const fn file_int(self) -> isize {
         self.1
     }
+
+    pub fn in_palace(self, color: PieceColor) -> bool {
+        let ranks = match color {
+            PieceColor::Red => 0..=2,
+            PieceColor::Black => 7..=9,
+        };
+        (3..=5).contains(&self.file()) && ranks.contains(&self.rank())
+    }
 }
 
 impl From<MoveDir> for Position {
@@ -301,11 +339,34 @@ impl From<Position> for String {
     }
 }
 
-impl From<String> for Position {
-    fn from(value: String) -> Self {
+impl TryFrom<String> for Position {
+    type Error = Error;
+    fn try_from(value: String) -> Result<Self, Error> {
+        value.as_str().try_into()
+    }
+}
+
+impl TryFrom<&str> for Position {
+    type Error = Error;
+    fn try_from(value: &str) -> Result<Self, Error> {
         let mut chars = value.chars();
-        let rank = chars.next().unwrap() as u8 - b'0';
-        let file = chars.next().unwrap() as u8 - b'0';
-        Position(rank as isize, file as isize)
+        let mut digit = |offset, expected, limit| {
+            let c = chars
+                .next()
+                .ok_or(Error::UnexpectedEnd { offset, expected })?;
+            c.to_digit(10)
+                .filter(|digit| *digit < limit)
+                .ok_or(Error::BadChar {
+                    offset,
+                    c,
+                    expected,
+                })
+        };
+        let rank = digit(0, "rank", RANKS as u32)?;
+        let file = digit(1, "file", FILES as u32)?;
+        if chars.next().is_some() {
+            return Err(Error::TrailingInput { offset: 2 });
+        }
+        Ok(Position::new(rank as usize, file as usize))
     }
 }
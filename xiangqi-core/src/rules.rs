@@ -0,0 +1,182 @@
+use super::*;
+
+/// How many times a position must occur before the repetition is judged.
+pub const REPETITIONS: usize = 3;
+
+/// Which rules decide the outcome of a repeated position.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RuleSet {
+    /// The Asian rules used by the WXF.
+    #[default]
+    Asian,
+    /// The Asian rules, except that a pawn that has crossed the river can also be chased, as
+    /// the Chinese Xiangqi Association counts it. Its other differences are not modelled.
+    CrossedPawns,
+}
+
+/// What one side did throughout a repetition cycle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Pattern {
+    /// At least one move neither checked nor chased.
+    Idle,
+    /// Every move gave check.
+    Check,
+    /// Every move gave check or chased a piece, and at least one chased.
+    Chase,
+}
+
+impl Pattern {
+    pub fn is_forbidden(self) -> bool {
+        self != Pattern::Idle
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Verdict {
+    Draw,
+    Loses(PieceColor),
+}
+
+/// A position that occurred [`REPETITIONS`] times, with what each side did in between.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Repetition {
+    pub red: Pattern,
+    pub black: Pattern,
+}
+
+impl Repetition {
+    pub fn pattern(&self, color: PieceColor) -> Pattern {
+        match color {
+            PieceColor::Red => self.red,
+            PieceColor::Black => self.black,
+        }
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        match (self.red, self.black) {
+            (red, black) if red == black => Verdict::Draw,
+            // Checking is the more serious offence, so the checking side must deviate
+            (Pattern::Check, _) => Verdict::Loses(PieceColor::Red),
+            (_, Pattern::Check) => Verdict::Loses(PieceColor::Black),
+            (Pattern::Chase, _) => Verdict::Loses(PieceColor::Red),
+            (_, Pattern::Chase) => Verdict::Loses(PieceColor::Black),
+            _ => Verdict::Draw,
+        }
+    }
+
+    /// Judges what each side did while playing `moves` from `board` and back to it.
+    pub(crate) fn of_cycle(board: &Board, moves: &[Move], rules: RuleSet) -> Self {
+        let mut board = board.clone();
+        let mut patterns = [Pattern::Check; 2];
+        for mv in moves.iter().copied() {
+            let color = board.turn();
+            let before = board.chased(color, rules);
+            board.make_move(mv);
+            let pattern = if board.is_check() {
+                Pattern::Check
+            } else {
+                // Only a threat that was not already there counts as a chase
+                let after = board.chased(color, rules);
+                if after.iter().any(|pos| !before.contains(pos)) {
+                    Pattern::Chase
+                } else {
+                    Pattern::Idle
+                }
+            };
+            let side = &mut patterns[u8::from(color) as usize];
+            *side = match (*side, pattern) {
+                (Pattern::Idle, _) | (_, Pattern::Idle) => Pattern::Idle,
+                (Pattern::Check, Pattern::Check) => Pattern::Check,
+                _ => Pattern::Chase,
+            };
+        }
+        Repetition {
+            red: patterns[0],
+            black: patterns[1],
+        }
+    }
+}
+
+fn value(kind: PieceKind) -> u32 {
+    match kind {
+        PieceKind::Empty | PieceKind::King => 0,
+        PieceKind::Pawn => 1,
+        PieceKind::Advisor | PieceKind::Bishop => 2,
+        PieceKind::Cannon | PieceKind::Knight => 4,
+        PieceKind::Rook => 9,
+    }
+}
+
+impl Board {
+    /// Squares of pieces that `by` threatens to win.
+    ///
+    /// A piece counts if it can be taken legally and is either unprotected or worth more than
+    /// its attacker. Kings and pawns may attack freely, so they never chase.
+    fn chased(&self, by: PieceColor, rules: RuleSet) -> HashSet<Position> {
+        let mut board = self.clone();
+        if board.turn() != by {
+            board.next_turn();
+        }
+        let mut moves = MoveList::new();
+        board.pseudo_moves(&mut moves);
+
+        let mut result = HashSet::new();
+        for mv in moves.iter().copied() {
+            let attacker = board.get(mv.from);
+            let target = board.get(mv.to);
+            let chaseable = match target.kind() {
+                PieceKind::Empty | PieceKind::King => false,
+                PieceKind::Pawn => {
+                    rules == RuleSet::CrossedPawns && crossed_river(mv.to, target.color().unwrap())
+                }
+                _ => true,
+            };
+            if !chaseable
+                || attacker.is_kind(PieceKind::King)
+                || attacker.is_kind(PieceKind::Pawn)
+                || !board.is_legal(mv)
+            {
+                continue;
+            }
+            let mut after = board.clone();
+            after.make_move(mv);
+            let protected = after.is_attacked(mv.to, by.opposite());
+            if !protected || value(target.kind()) > value(attacker.kind()) {
+                result.insert(mv.to);
+            }
+        }
+        result
+    }
+}
+
+impl GameHistory {
+    /// Judges the current position if it has occurred [`REPETITIONS`] times since the last
+    /// capture.
+    pub fn repetition(&self, rules: RuleSet) -> Option<Repetition> {
+        let undos = self.undos();
+        let current = self.board().zobrist();
+        let mut count = 1;
+        let mut first = None;
+        for (i, undo) in undos.iter().enumerate().rev() {
+            if !undo.captured.is_empty() {
+                break;
+            }
+            if undo.zobrist == current {
+                count += 1;
+                first = Some(i);
+            }
+        }
+        if count < REPETITIONS {
+            return None;
+        }
+
+        let first = first?;
+        let mut board = self.board().clone();
+        for undo in undos[first..].iter().rev() {
+            board.unmake_move(*undo);
+        }
+
+        let moves: Vec<Move> = undos[first..].iter().map(|undo| undo.mv).collect();
+        Some(Repetition::of_cycle(&board, &moves, rules))
+    }
+}
@@ -0,0 +1,587 @@
+use super::*;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// The score of mating on the spot; mates further away score less.
+pub const MATE: i32 = 30_000;
+/// Scores beyond this are mates, whose distance is `MATE - score.abs()` plies.
+pub const MATE_BOUND: i32 = MATE - 1_000;
+/// Evaluations are cut off here, so that no weights can make a position look like a mate.
+pub const MAX_EVAL: i32 = MATE_BOUND / 2;
+pub const MAX_DEPTH: u32 = 64;
+const MAX_PLY: usize = 96;
+const INFINITY: i32 = MATE + 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SearchLimits {
+    pub depth: u32,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+}
+
+impl Default for SearchLimits {
+    /// Searches until stopped.
+    fn default() -> Self {
+        Self {
+            depth: MAX_DEPTH,
+            nodes: None,
+            time: None,
+        }
+    }
+}
+
+impl SearchLimits {
+    pub fn depth(depth: u32) -> Self {
+        Self {
+            depth,
+            ..Default::default()
+        }
+    }
+
+    pub fn time(time: Duration) -> Self {
+        Self {
+            time: Some(time),
+            ..Default::default()
+        }
+    }
+}
+
+/// What a finished iteration found, from the side to move's point of view.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchInfo {
+    pub depth: u32,
+    pub score: i32,
+    pub nodes: u64,
+    pub time: Duration,
+    pub pv: Vec<Move>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Bound {
+    Exact,
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry {
+    mv: Option<Move>,
+    score: i32,
+    depth: u32,
+    bound: Bound,
+}
+
+const NO_MOVE: u64 = 0xFFFF;
+
+impl Entry {
+    fn pack(self) -> u64 {
+        let mv = self
+            .mv
+            .map_or(NO_MOVE, |mv| (index(mv.from) << 7 | index(mv.to)) as u64);
+        let bound = match self.bound {
+            Bound::Exact => 1,
+            Bound::Lower => 2,
+            Bound::Upper => 3,
+        };
+        mv | (self.score as i16 as u16 as u64) << 16
+            | (self.depth.min(255) as u64) << 32
+            | bound << 40
+    }
+
+    fn unpack(data: u64) -> Option<Self> {
+        let bound = match data >> 40 & 0xFF {
+            1 => Bound::Exact,
+            2 => Bound::Lower,
+            3 => Bound::Upper,
+            _ => return None,
+        };
+        let square = |index: u64| Position::new(index as usize / FILES, index as usize % FILES);
+        let mv = data & 0xFFFF;
+        Some(Self {
+            mv: (mv != NO_MOVE).then(|| Move::new(square(mv >> 7), square(mv & 0x7F))),
+            score: (data >> 16) as u16 as i16 as i32,
+            depth: (data >> 32 & 0xFF) as u32,
+            bound,
+        })
+    }
+}
+
+/// A transposition table slot that threads can share without locking.
+///
+/// The key is stored mixed with the data, so that a slot torn by two threads writing at once
+/// never matches and is simply ignored.
+#[derive(Default)]
+struct Slot {
+    key: AtomicU64,
+    data: AtomicU64,
+}
+
+/// Mate scores are stored relative to the node, so they stay right wherever the node is found.
+fn to_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > MATE_BOUND => score + ply as i32,
+        score if score < -MATE_BOUND => score - ply as i32,
+        score => score,
+    }
+}
+
+fn from_table(score: i32, ply: usize) -> i32 {
+    match score {
+        score if score > MATE_BOUND => score - ply as i32,
+        score if score < -MATE_BOUND => score + ply as i32,
+        score => score,
+    }
+}
+
+/// An iterative deepening alpha-beta searcher.
+///
+/// It keeps its transposition table and move ordering statistics between searches, so reusing
+/// one searcher over a game helps.
+pub struct Searcher {
+    table: Arc<Vec<Slot>>,
+    killers: [[Option<Move>; 2]; MAX_PLY],
+    history: Vec<u32>,
+    /// Hashes of the positions leading to the current node.
+    path: Vec<u64>,
+    /// The moves played from each of those positions.
+    line: Vec<Move>,
+    stop: Arc<AtomicBool>,
+    limits: SearchLimits,
+    start: Instant,
+    nodes: u64,
+    aborted: bool,
+    best: Option<Move>,
+    weights: Weights,
+    noise: i32,
+    /// Picks the noise of each position, and changes with every search.
+    seed: u64,
+    /// Searchers running alongside this one, sharing its table.
+    helpers: Vec<Searcher>,
+}
+
+impl Default for Searcher {
+    fn default() -> Self {
+        Self::new(16)
+    }
+}
+
+impl Searcher {
+    /// Creates a searcher whose transposition table takes about `hash_mb` megabytes.
+    pub fn new(hash_mb: usize) -> Self {
+        let entries = (hash_mb << 20) / std::mem::size_of::<Slot>();
+        Self {
+            table: Arc::new((0..entries.max(1)).map(|_| Slot::default()).collect()),
+            killers: [[None; 2]; MAX_PLY],
+            history: vec![0; SQUARES * SQUARES],
+            path: Vec::with_capacity(MAX_PLY),
+            line: Vec::with_capacity(MAX_PLY),
+            stop: Arc::new(AtomicBool::new(false)),
+            limits: SearchLimits::default(),
+            start: Instant::now(),
+            nodes: 0,
+            aborted: false,
+            best: None,
+            weights: Weights::default(),
+            noise: 0,
+            seed: 0,
+            helpers: Vec::new(),
+        }
+    }
+
+    /// Searches on `threads` threads from now on.
+    ///
+    /// The extra threads search the same position, and help through the shared table.
+    pub fn set_threads(&mut self, threads: usize) {
+        let helpers = threads.max(1) - 1;
+        self.helpers.truncate(helpers);
+        while self.helpers.len() < helpers {
+            let helper = Self {
+                table: self.table.clone(),
+                stop: self.stop.clone(),
+                weights: self.weights.clone(),
+                noise: self.noise,
+                ..Self::new(0)
+            };
+            self.helpers.push(helper);
+        }
+    }
+
+    pub fn threads(&self) -> usize {
+        self.helpers.len() + 1
+    }
+
+    /// A flag that makes a running search return as soon as possible once it is set.
+    ///
+    /// Each search clears the flag when it starts.
+    pub fn stop_flag(&self) -> Arc<AtomicBool> {
+        self.stop.clone()
+    }
+
+    pub fn weights(&self) -> &Weights {
+        &self.weights
+    }
+
+    /// Evaluates positions with `weights` from now on.
+    pub fn set_weights(&mut self, weights: Weights) {
+        for helper in self.helpers.iter_mut() {
+            helper.weights = weights.clone();
+        }
+        self.weights = weights;
+        // Stored scores came from the old weights
+        self.clear();
+    }
+
+    /// Randomly changes the evaluation of every position by up to `noise`, which makes the
+    /// search weaker and less predictable.
+    pub fn set_noise(&mut self, noise: i32) {
+        self.noise = noise.max(0);
+        for helper in self.helpers.iter_mut() {
+            helper.noise = self.noise;
+        }
+    }
+
+    /// Forgets everything learnt from earlier searches.
+    pub fn clear(&mut self) {
+        for slot in self.table.iter() {
+            slot.key.store(0, Ordering::Relaxed);
+            slot.data.store(0, Ordering::Relaxed);
+        }
+        self.killers = [[None; 2]; MAX_PLY];
+        self.history.fill(0);
+        for helper in self.helpers.iter_mut() {
+            helper.killers = [[None; 2]; MAX_PLY];
+            helper.history.fill(0);
+        }
+    }
+
+    /// Searches `board` within `limits`, reporting every finished iteration, and returns the
+    /// best move found, or `None` if the side to move has no legal move.
+    pub fn search(
+        &mut self,
+        board: &Board,
+        limits: SearchLimits,
+        report: impl FnMut(&SearchInfo),
+    ) -> Option<Move> {
+        self.stop.store(false, Ordering::Relaxed);
+        if self.noise > 0 {
+            self.seed = random();
+            for helper in self.helpers.iter_mut() {
+                helper.seed = self.seed;
+            }
+        }
+        if self.helpers.is_empty() {
+            return self.deepen(board, limits, 1, report);
+        }
+        let mut helpers = std::mem::take(&mut self.helpers);
+        let result = std::thread::scope(|scope| {
+            for (i, helper) in helpers.iter_mut().enumerate() {
+                // Starting some helpers a ply deeper spreads the threads over different depths
+                let first = 1 + (i % 2) as u32;
+                scope.spawn(move || helper.deepen(board, limits, first, |_| {}));
+            }
+            let result = self.deepen(board, limits, 1, report);
+            self.stop.store(true, Ordering::Relaxed);
+            result
+        });
+        self.helpers = helpers;
+        result
+    }
+
+    fn deepen(
+        &mut self,
+        board: &Board,
+        limits: SearchLimits,
+        first: u32,
+        mut report: impl FnMut(&SearchInfo),
+    ) -> Option<Move> {
+        self.limits = limits;
+        self.start = Instant::now();
+        self.nodes = 0;
+        self.aborted = false;
+        self.killers = [[None; 2]; MAX_PLY];
+        self.history.iter_mut().for_each(|score| *score /= 8);
+
+        let mut board = board.clone();
+        let mut moves = MoveList::new();
+        board.legal_moves_into(&mut moves);
+        let mut result = *moves.first()?;
+        for depth in first.min(limits.depth).max(1)..=limits.depth.clamp(1, MAX_DEPTH) {
+            self.best = None;
+            self.path.clear();
+            self.line.clear();
+            let score = self.alpha_beta(&mut board, depth, 0, -INFINITY, INFINITY);
+            if self.aborted {
+                break;
+            }
+            result = self.best.unwrap_or(result);
+            report(&SearchInfo {
+                depth,
+                score,
+                nodes: self.nodes,
+                time: self.start.elapsed(),
+                pv: self.pv(&mut board, result, depth),
+            });
+            // A found mate will not get any better, and the next iteration would not finish
+            if score.abs() > MATE_BOUND
+                || limits
+                    .time
+                    .is_some_and(|time| self.start.elapsed() * 2 > time)
+            {
+                break;
+            }
+        }
+        Some(result)
+    }
+
+    fn should_stop(&mut self) -> bool {
+        self.aborted |= self.limits.nodes.is_some_and(|nodes| self.nodes > nodes);
+        // Looking at the clock is slow, so it is only done now and then
+        if self.nodes & 1023 == 0 {
+            self.aborted |= self.stop.load(Ordering::Relaxed)
+                || self
+                    .limits
+                    .time
+                    .is_some_and(|time| self.start.elapsed() >= time);
+        }
+        self.aborted
+    }
+
+    fn evaluate(&self, board: &Board) -> i32 {
+        let mut score = board.evaluate(&self.weights);
+        if self.noise != 0 {
+            // The same position always gets the same noise within a search
+            let hash = (board.zobrist() ^ self.seed).wrapping_mul(0x9E37_79B9_7F4A_7C15) >> 32;
+            score += (hash % (2 * self.noise as u64 + 1)) as i32 - self.noise;
+        }
+        score.clamp(-MAX_EVAL, MAX_EVAL)
+    }
+
+    fn slot(&self, key: u64) -> usize {
+        (key % self.table.len() as u64) as usize
+    }
+
+    fn probe(&self, key: u64) -> Option<Entry> {
+        let slot = &self.table[self.slot(key)];
+        let data = slot.data.load(Ordering::Relaxed);
+        if slot.key.load(Ordering::Relaxed) ^ data != key {
+            return None;
+        }
+        Entry::unpack(data)
+    }
+
+    fn store(&self, key: u64, entry: Entry) {
+        let slot = &self.table[self.slot(key)];
+        let data = entry.pack();
+        slot.key.store(key ^ data, Ordering::Relaxed);
+        slot.data.store(data, Ordering::Relaxed);
+    }
+
+    fn order(&self, board: &Board, mv: Move, hash_move: Option<Move>, ply: usize) -> i32 {
+        let victim = board.get(mv.to);
+        if Some(mv) == hash_move {
+            i32::MAX
+        } else if !victim.is_empty() {
+            // Most valuable victim first, least valuable attacker to break ties
+            let attacker = self.weights.value(board.get(mv.from).kind());
+            1 << 24 | self.weights.value(victim.kind()) << 10 | (1023 - attacker.clamp(0, 1023))
+        } else if let Some(slot) = self.killers[ply]
+            .iter()
+            .position(|killer| *killer == Some(mv))
+        {
+            (1 << 23) - slot as i32
+        } else {
+            self.history[index(mv.from) * SQUARES + index(mv.to)].min(1 << 22) as i32
+        }
+    }
+
+    /// Scores a position met before on the path to it, by who the rules blame for the cycle.
+    fn repetition(&self, board: &Board, first: usize, ply: usize) -> i32 {
+        let cycle = Repetition::of_cycle(board, &self.line[first..], RuleSet::default());
+        match cycle.verdict() {
+            Verdict::Draw => 0,
+            // Losing by the rules ends the game just like being mated
+            Verdict::Loses(color) if color == board.turn() => -MATE + ply as i32,
+            Verdict::Loses(_) => MATE - ply as i32,
+        }
+    }
+
+    fn alpha_beta(
+        &mut self,
+        board: &mut Board,
+        depth: u32,
+        ply: usize,
+        mut alpha: i32,
+        beta: i32,
+    ) -> i32 {
+        self.nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+        if let Some(first) = self.path.iter().rposition(|key| *key == board.zobrist()) {
+            return self.repetition(board, first, ply);
+        }
+        if ply >= MAX_PLY - 1 {
+            return self.evaluate(board);
+        }
+        let in_check = board.is_check();
+        // Never stop searching while in check
+        let depth = depth + in_check as u32;
+        if depth == 0 {
+            return self.quiesce(board, ply, alpha, beta);
+        }
+
+        let key = board.zobrist();
+        let entry = self.probe(key);
+        if let Some(entry) = entry.filter(|entry| ply > 0 && entry.depth >= depth) {
+            let score = from_table(entry.score, ply);
+            match entry.bound {
+                Bound::Exact => return score,
+                Bound::Lower if score >= beta => return score,
+                Bound::Upper if score <= alpha => return score,
+                _ => {}
+            }
+        }
+        let hash_move = entry.and_then(|entry| entry.mv);
+
+        let mut moves = MoveList::new();
+        board.legal_moves_into(&mut moves);
+        if moves.is_empty() {
+            // Being stalemated loses as well
+            return -MATE + ply as i32;
+        }
+        moves.sort_unstable_by_key(|mv| -self.order(board, *mv, hash_move, ply));
+
+        let original = alpha;
+        let mut best = (-INFINITY, None);
+        self.path.push(key);
+        for (i, mv) in moves.iter().copied().enumerate() {
+            let quiet = board.get(mv.to).is_empty();
+            let undo = board.make_move(mv);
+            self.line.push(mv);
+            let mut score;
+            if i == 0 {
+                score = -self.alpha_beta(board, depth - 1, ply + 1, -beta, -alpha);
+            } else {
+                // Later moves are expected to be worse, which a null window proves cheaply
+                score = -self.alpha_beta(board, depth - 1, ply + 1, -alpha - 1, -alpha);
+                if score > alpha && score < beta {
+                    score = -self.alpha_beta(board, depth - 1, ply + 1, -beta, -alpha);
+                }
+            }
+            board.unmake_move(undo);
+            self.line.pop();
+            if self.aborted {
+                self.path.pop();
+                return 0;
+            }
+
+            if score > best.0 {
+                best = (score, Some(mv));
+            }
+            if score > alpha {
+                alpha = score;
+                if ply == 0 {
+                    self.best = Some(mv);
+                }
+            }
+            if alpha >= beta {
+                if quiet {
+                    let killers = &mut self.killers[ply];
+                    if killers[0] != Some(mv) {
+                        killers[1] = killers[0];
+                        killers[0] = Some(mv);
+                    }
+                    let score = &mut self.history[index(mv.from) * SQUARES + index(mv.to)];
+                    *score = score.saturating_add(depth * depth);
+                }
+                break;
+            }
+        }
+        self.path.pop();
+
+        let bound = if best.0 <= original {
+            Bound::Upper
+        } else if best.0 >= beta {
+            Bound::Lower
+        } else {
+            Bound::Exact
+        };
+        self.store(
+            key,
+            Entry {
+                mv: best.1,
+                score: to_table(best.0, ply),
+                depth,
+                bound,
+            },
+        );
+        best.0
+    }
+
+    /// Plays out captures until the position is quiet, so that the search never stops in the
+    /// middle of an exchange.
+    fn quiesce(&mut self, board: &mut Board, ply: usize, mut alpha: i32, beta: i32) -> i32 {
+        self.nodes += 1;
+        if self.should_stop() {
+            return 0;
+        }
+        if ply >= MAX_PLY - 1 {
+            return self.evaluate(board);
+        }
+        let in_check = board.is_check();
+        let mut best = -MATE + ply as i32;
+        if !in_check {
+            best = self.evaluate(board);
+            if best >= beta {
+                return best;
+            }
+            alpha = alpha.max(best);
+        }
+
+        let mut moves = MoveList::new();
+        board.pseudo_moves(&mut moves);
+        // Out of check every move must be tried, otherwise only captures
+        if !in_check {
+            moves.retain(|mv| !board.get(mv.to).is_empty());
+        }
+        moves.retain(|mv| board.is_safe(mv));
+        moves.sort_unstable_by_key(|mv| -self.order(board, *mv, None, ply));
+
+        for mv in moves.iter().copied() {
+            let undo = board.make_move(mv);
+            let score = -self.quiesce(board, ply + 1, -beta, -alpha);
+            board.unmake_move(undo);
+            if self.aborted {
+                return 0;
+            }
+            best = best.max(score);
+            alpha = alpha.max(score);
+            if alpha >= beta {
+                break;
+            }
+        }
+        best
+    }
+
+    /// Follows the transposition table from the root to recover the expected line of play.
+    fn pv(&self, board: &mut Board, first: Move, depth: u32) -> Vec<Move> {
+        let mut pv = vec![first];
+        let mut undos = vec![board.make_move(first)];
+        while pv.len() < depth as usize {
+            let Some(mv) = self.probe(board.zobrist()).and_then(|entry| entry.mv) else {
+                break;
+            };
+            if !board.is_legal(mv) || undos.iter().any(|undo| undo.zobrist == board.zobrist()) {
+                break;
+            }
+            pv.push(mv);
+            undos.push(board.make_move(mv));
+        }
+        undos
+            .into_iter()
+            .rev()
+            .for_each(|undo| board.unmake_move(undo));
+        pv
+    }
+}
@@ -0,0 +1,451 @@
+use super::*;
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+
+/// How long an engine may take to answer anything but a search.
+pub const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The two text protocols xiangqi engines speak.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Protocol {
+    Ucci,
+    /// The xiangqi dialect of UCI, as spoken by Pikafish.
+    Uci,
+}
+
+impl Protocol {
+    pub fn name(self) -> &'static str {
+        match self {
+            Protocol::Ucci => "ucci",
+            Protocol::Uci => "uci",
+        }
+    }
+
+    pub fn ok(self) -> &'static str {
+        match self {
+            Protocol::Ucci => "ucciok",
+            Protocol::Uci => "uciok",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Score {
+    /// Centipawns, from the side to move's point of view.
+    Cp(i32),
+    /// Moves to mate, negative when being mated.
+    Mate(i32),
+}
+
+impl Score {
+    /// Reads a plain score as a mate when it is within `MATE - MATE_BOUND` of `mate`, the score
+    /// an engine writes for mating on the spot.
+    ///
+    /// UCCI has no mate scores, and every engine picks its own: this crate writes [`MATE`] less
+    /// the moves to mate, while ElephantEye mates at 10000.
+    pub fn with_mate_score(self, mate: i32) -> Self {
+        match self {
+            Score::Cp(cp) if cp.abs() > mate - (MATE - MATE_BOUND) => {
+                Score::Mate((mate - cp.abs()) * cp.signum())
+            }
+            score => score,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EngineInfo {
+    pub depth: Option<u32>,
+    pub score: Option<Score>,
+    pub nodes: Option<u64>,
+    pub time: Option<Duration>,
+    pub pv: Vec<Move>,
+    pub string: Option<String>,
+}
+
+impl From<&SearchInfo> for EngineInfo {
+    fn from(info: &SearchInfo) -> Self {
+        let score = if info.score.abs() > MATE_BOUND {
+            // Plies to mate, rounded up to whole moves
+            let moves = (MATE - info.score.abs() + 1) / 2;
+            Score::Mate(moves * info.score.signum())
+        } else {
+            Score::Cp(info.score)
+        };
+        Self {
+            depth: Some(info.depth),
+            score: Some(score),
+            nodes: Some(info.nodes),
+            time: Some(info.time),
+            pv: info.pv.clone(),
+            string: None,
+        }
+    }
+}
+
+impl EngineInfo {
+    /// The `info` line an engine speaking `protocol` writes for this.
+    pub fn to_line(&self, protocol: Protocol) -> String {
+        let mut line = "info".to_string();
+        if let Some(depth) = self.depth {
+            line.push_str(&format!(" depth {}", depth));
+        }
+        match (self.score, protocol) {
+            (Some(Score::Cp(cp)), Protocol::Ucci) => line.push_str(&format!(" score {}", cp)),
+            (Some(Score::Cp(cp)), Protocol::Uci) => line.push_str(&format!(" score cp {}", cp)),
+            // UCCI has no mate scores, so mates are written as huge scores
+            (Some(Score::Mate(moves)), Protocol::Ucci) => {
+                line.push_str(&format!(" score {}", (MATE - moves.abs()) * moves.signum()))
+            }
+            (Some(Score::Mate(moves)), Protocol::Uci) => {
+                line.push_str(&format!(" score mate {}", moves))
+            }
+            (None, _) => {}
+        }
+        if let Some(nodes) = self.nodes {
+            line.push_str(&format!(" nodes {}", nodes));
+        }
+        if let Some(time) = self.time {
+            line.push_str(&format!(" time {}", time.as_millis()));
+        }
+        if !self.pv.is_empty() {
+            line.push_str(" pv");
+            for mv in self.pv.iter() {
+                line.push(' ');
+                line.push_str(&mv.to_iccs());
+            }
+        }
+        if let Some(ref string) = self.string {
+            line.push_str(" string ");
+            line.push_str(string);
+        }
+        line
+    }
+}
+
+/// A line an engine writes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineMessage {
+    Id {
+        key: String,
+        value: String,
+    },
+    /// An option declaration, kept as written after `option`.
+    Option(String),
+    /// `ucciok` or `uciok`.
+    Ok,
+    ReadyOk,
+    Info(EngineInfo),
+    BestMove {
+        mv: Move,
+        ponder: Option<Move>,
+    },
+    /// The engine has no move to play, written `nobestmove` or `bestmove (none)`.
+    NoBestMove,
+    Bye,
+    Other(String),
+}
+
+impl EngineMessage {
+    pub fn parse(line: &str) -> Self {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("id") => match words.next() {
+                Some(key) => EngineMessage::Id {
+                    key: key.to_string(),
+                    value: words.collect::<Vec<_>>().join(" "),
+                },
+                None => EngineMessage::Other(line.to_string()),
+            },
+            Some("option") => EngineMessage::Option(words.collect::<Vec<_>>().join(" ")),
+            Some("ucciok") | Some("uciok") => EngineMessage::Ok,
+            Some("readyok") => EngineMessage::ReadyOk,
+            Some("info") => EngineMessage::Info(parse_info(words)),
+            Some("bestmove") => {
+                let Some(Ok(mv)) = words.next().map(str::parse) else {
+                    return EngineMessage::NoBestMove;
+                };
+                let ponder = match (words.next(), words.next()) {
+                    (Some("ponder"), Some(ponder)) => ponder.parse().ok(),
+                    _ => None,
+                };
+                EngineMessage::BestMove { mv, ponder }
+            }
+            Some("nobestmove") => EngineMessage::NoBestMove,
+            Some("bye") => EngineMessage::Bye,
+            _ => EngineMessage::Other(line.to_string()),
+        }
+    }
+}
+
+fn number<'a>(words: &mut impl Iterator<Item = &'a str>) -> Option<i64> {
+    words.next().and_then(|word| word.parse().ok())
+}
+
+fn parse_info<'a>(mut words: impl Iterator<Item = &'a str>) -> EngineInfo {
+    let mut info = EngineInfo::default();
+    while let Some(word) = words.next() {
+        match word {
+            "depth" => info.depth = number(&mut words).map(|depth| depth as u32),
+            "nodes" => info.nodes = number(&mut words).map(|nodes| nodes as u64),
+            "time" => info.time = number(&mut words).map(|ms| Duration::from_millis(ms as u64)),
+            // UCCI leaves out the unit, and writes mates as huge scores on its own scale
+            "score" => {
+                info.score = match words.next() {
+                    Some("cp") => number(&mut words).map(|cp| Score::Cp(cp as i32)),
+                    Some("mate") => number(&mut words).map(|mate| Score::Mate(mate as i32)),
+                    Some(cp) => cp.parse().ok().map(Score::Cp),
+                    None => None,
+                }
+            }
+            // Both run to the end of the line
+            "pv" => {
+                info.pv = words.by_ref().map_while(|word| word.parse().ok()).collect();
+            }
+            "string" => info.string = Some(words.by_ref().collect::<Vec<_>>().join(" ")),
+            _ => {}
+        }
+    }
+    info
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EngineError {
+    Spawn(String),
+    Io(String),
+    /// The engine exited or closed its output.
+    Closed,
+    /// The engine took longer than allowed to answer.
+    Timeout,
+}
+
+impl std::fmt::Display for EngineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            EngineError::Spawn(err) => write!(f, "Unable to start the engine: {}", err),
+            EngineError::Io(err) => write!(f, "Unable to talk to the engine: {}", err),
+            EngineError::Closed => write!(f, "The engine has quit"),
+            EngineError::Timeout => write!(f, "The engine does not answer"),
+        }
+    }
+}
+
+impl std::error::Error for EngineError {}
+
+/// An engine executable running as a child process.
+///
+/// Its output is read on a separate thread, so that every wait can have a timeout. This is only
+/// the host side: the game's computer player always uses the built-in [`Searcher`], and the
+/// `xiangqi-engine` binary is the one engine tested against it.
+pub struct ExternalEngine {
+    protocol: Protocol,
+    /// Whether a UCCI engine takes times in milliseconds rather than seconds.
+    millis: bool,
+    /// The score a UCCI engine writes for mating on the spot, once known.
+    mate: Option<i32>,
+    child: Child,
+    stdin: ChildStdin,
+    lines: Receiver<String>,
+    /// Everything the engine said about itself, such as its `name` and `author`.
+    pub ids: Vec<(String, String)>,
+    pub options: Vec<String>,
+}
+
+impl ExternalEngine {
+    /// Starts the engine and completes the protocol handshake.
+    pub fn launch(path: impl AsRef<Path>, protocol: Protocol) -> Result<Self, EngineError> {
+        let mut child = Command::new(path.as_ref())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| EngineError::Spawn(err.to_string()))?;
+        let stdin = child.stdin.take().unwrap();
+        let stdout = child.stdout.take().unwrap();
+        let (sender, lines) = channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if sender.send(line).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let mut engine = Self {
+            protocol,
+            millis: protocol == Protocol::Uci,
+            mate: None,
+            child,
+            stdin,
+            lines,
+            ids: Vec::new(),
+            options: Vec::new(),
+        };
+        engine.send(protocol.name())?;
+        loop {
+            match engine.recv(HANDSHAKE_TIMEOUT)? {
+                EngineMessage::Id { key, value } => engine.ids.push((key, value)),
+                EngineMessage::Option(option) => engine.options.push(option),
+                EngineMessage::Ok => break,
+                _ => {}
+            }
+        }
+        if !engine.millis
+            && engine
+                .options
+                .iter()
+                .any(|option| option.starts_with("usemillisec"))
+        {
+            engine.set_option("usemillisec", "true")?;
+            engine.millis = true;
+        }
+        Ok(engine)
+    }
+
+    pub fn protocol(&self) -> Protocol {
+        self.protocol
+    }
+
+    pub fn name(&self) -> Option<&str> {
+        self.ids
+            .iter()
+            .find(|(key, _)| key == "name")
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Writes one command line.
+    pub fn send(&mut self, command: &str) -> Result<(), EngineError> {
+        writeln!(self.stdin, "{}", command)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|err| EngineError::Io(err.to_string()))
+    }
+
+    /// Reads the engine's plain scores near `mate` as mates, see [`Score::with_mate_score`].
+    ///
+    /// Without this only `score mate` is a mate.
+    pub fn set_mate_score(&mut self, mate: i32) {
+        self.mate = Some(mate);
+    }
+
+    /// Waits for the next line the engine writes.
+    pub fn recv(&mut self, timeout: Duration) -> Result<EngineMessage, EngineError> {
+        match self.lines.recv_timeout(timeout) {
+            Ok(line) => Ok(match (EngineMessage::parse(&line), self.mate) {
+                (EngineMessage::Info(mut info), Some(mate)) => {
+                    info.score = info.score.map(|score| score.with_mate_score(mate));
+                    EngineMessage::Info(info)
+                }
+                (message, _) => message,
+            }),
+            Err(RecvTimeoutError::Timeout) => Err(EngineError::Timeout),
+            Err(RecvTimeoutError::Disconnected) => Err(EngineError::Closed),
+        }
+    }
+
+    pub fn wait_ready(&mut self) -> Result<(), EngineError> {
+        self.send("isready")?;
+        while self.recv(HANDSHAKE_TIMEOUT)? != EngineMessage::ReadyOk {}
+        Ok(())
+    }
+
+    pub fn set_option(&mut self, name: &str, value: &str) -> Result<(), EngineError> {
+        match self.protocol {
+            Protocol::Ucci => self.send(&format!("setoption {} {}", name, value)),
+            Protocol::Uci => self.send(&format!("setoption name {} value {}", name, value)),
+        }
+    }
+
+    /// Sets up the position reached by playing `moves` from `start`.
+    pub fn position(&mut self, start: &Board, moves: &[Move]) -> Result<(), EngineError> {
+        let mut command = format!("position fen {}", start.to_fen());
+        if !moves.is_empty() {
+            command.push_str(" moves");
+            for mv in moves {
+                command.push(' ');
+                command.push_str(&mv.to_iccs());
+            }
+        }
+        self.send(&command)
+    }
+
+    pub fn history(&mut self, history: &GameHistory) -> Result<(), EngineError> {
+        let moves: Vec<Move> = history.moves().collect();
+        self.position(history.start(), &moves)
+    }
+
+    /// Starts searching the current position within `limits`.
+    ///
+    /// Without any limit the engine searches until [`ExternalEngine::stop`].
+    pub fn go(&mut self, limits: &SearchLimits) -> Result<(), EngineError> {
+        let mut command = "go".to_string();
+        if limits.depth < MAX_DEPTH {
+            command.push_str(&format!(" depth {}", limits.depth));
+        }
+        if let Some(nodes) = limits.nodes {
+            command.push_str(&format!(" nodes {}", nodes));
+        }
+        if let Some(time) = limits.time {
+            match self.protocol {
+                // UCCI only knows the clock, which a single move to go turns into a move time
+                Protocol::Ucci if self.millis => {
+                    command.push_str(&format!(" time {} movestogo 1", time.as_millis()))
+                }
+                Protocol::Ucci => command.push_str(&format!(
+                    " time {} movestogo 1",
+                    time.as_millis().div_ceil(1000)
+                )),
+                Protocol::Uci => command.push_str(&format!(" movetime {}", time.as_millis())),
+            }
+        }
+        if command == "go" {
+            command.push_str(" infinite");
+        }
+        self.send(&command)
+    }
+
+    pub fn stop(&mut self) -> Result<(), EngineError> {
+        self.send("stop")
+    }
+
+    /// Reads the search output up to the best move, passing on every `info` line.
+    ///
+    /// `timeout` applies to each line, so a search that keeps reporting may take longer.
+    pub fn best_move(
+        &mut self,
+        timeout: Duration,
+        mut on_info: impl FnMut(&EngineInfo),
+    ) -> Result<Option<Move>, EngineError> {
+        loop {
+            match self.recv(timeout)? {
+                EngineMessage::Info(info) => on_info(&info),
+                EngineMessage::BestMove { mv, .. } => return Ok(Some(mv)),
+                EngineMessage::NoBestMove => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+
+    /// Asks the engine to exit, killing it if it does not.
+    pub fn quit(mut self) {
+        let _ = self.send("quit");
+        // UCCI engines say goodbye, UCI engines just leave
+        while let Ok(message) = self.recv(HANDSHAKE_TIMEOUT) {
+            if message == EngineMessage::Bye {
+                break;
+            }
+        }
+    }
+}
+
+impl Drop for ExternalEngine {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
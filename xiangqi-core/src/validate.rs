@@ -0,0 +1,173 @@
+use super::*;
+
+/// A way in which a position cannot arise in a real game.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    KingCount {
+        color: PieceColor,
+        count: usize,
+    },
+    /// The listed king position does not hold that side's king.
+    KingsMismatch {
+        color: PieceColor,
+        listed: Position,
+    },
+    OutsidePalace(Position),
+    /// An advisor off the diagonals of its palace.
+    AdvisorSquare(Position),
+    /// An elephant on a square elephants can never reach.
+    BishopSquare(Position),
+    /// A pawn behind its start rank, or on a file it cannot reach before the river.
+    PawnSquare(Position),
+    TooMany {
+        color: PieceColor,
+        kind: PieceKind,
+        count: usize,
+    },
+    KingsFacing,
+    /// The side that just moved left its own king in check.
+    WaitingSideInCheck,
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Violation::KingCount { color, count } => {
+                write!(f, "{:?} has {} kings instead of 1", color, count)
+            }
+            Violation::KingsMismatch { color, listed } => {
+                write!(f, "The {:?} king is not on {}", color, listed.to_iccs())
+            }
+            Violation::OutsidePalace(pos) => write!(f, "{} is outside the palace", pos.to_iccs()),
+            Violation::AdvisorSquare(pos) => write!(f, "No advisor can reach {}", pos.to_iccs()),
+            Violation::BishopSquare(pos) => {
+                write!(f, "No elephant can reach {}", pos.to_iccs())
+            }
+            Violation::PawnSquare(pos) => write!(f, "No pawn can reach {}", pos.to_iccs()),
+            Violation::TooMany { color, kind, count } => {
+                write!(f, "{:?} has {} pieces of kind {:?}", color, count, kind)
+            }
+            Violation::KingsFacing => write!(f, "The kings face each other"),
+            Violation::WaitingSideInCheck => write!(f, "The side not to move is in check"),
+        }
+    }
+}
+
+/// The most pieces of each kind a side starts with.
+const MAX_PIECES: [(PieceKind, usize); 7] = [
+    (PieceKind::Pawn, 5),
+    (PieceKind::Cannon, 2),
+    (PieceKind::King, 1),
+    (PieceKind::Advisor, 2),
+    (PieceKind::Bishop, 2),
+    (PieceKind::Knight, 2),
+    (PieceKind::Rook, 2),
+];
+
+/// Ranks counted from `color`'s own back rank.
+fn relative_rank(pos: Position, color: PieceColor) -> usize {
+    match color {
+        PieceColor::Red => pos.rank(),
+        PieceColor::Black => RANKS - 1 - pos.rank(),
+    }
+}
+
+fn piece_square(piece: Piece, pos: Position) -> Option<Violation> {
+    let color = piece.color()?;
+    let rank = relative_rank(pos, color);
+    match piece.kind() {
+        PieceKind::King if !pos.in_palace(color) => Some(Violation::OutsidePalace(pos)),
+        // Advisors only ever stand on the corners and the centre of the palace
+        PieceKind::Advisor if !pos.in_palace(color) || (rank + pos.file()).is_multiple_of(2) => {
+            Some(Violation::AdvisorSquare(pos))
+        }
+        PieceKind::Bishop
+            if rank > 4 || !rank.is_multiple_of(2) || (rank + pos.file()) % 4 != 2 =>
+        {
+            Some(Violation::BishopSquare(pos))
+        }
+        PieceKind::Pawn if rank < 3 || (rank < 5 && !pos.file().is_multiple_of(2)) => {
+            Some(Violation::PawnSquare(pos))
+        }
+        _ => None,
+    }
+}
+
+impl Board {
+    /// How many pieces each side has, indexed by colour and then by kind.
+    fn piece_counts(&self) -> [[usize; 8]; 2] {
+        let mut counts = [[0; 8]; 2];
+        for piece in self.content.iter() {
+            if let Some(color) = piece.color() {
+                counts[u8::from(color) as usize][u8::from(piece.kind()) as usize] += 1;
+            }
+        }
+        counts
+    }
+
+    /// The kinds a side has more of than a full set, kings included.
+    ///
+    /// A full set is what bounds the number of moves to [`MAX_MOVES`].
+    pub(crate) fn excess_pieces(&self) -> Vec<Violation> {
+        let counts = self.piece_counts();
+        let mut violations = Vec::new();
+        for color in [PieceColor::Red, PieceColor::Black] {
+            for (kind, max) in MAX_PIECES {
+                let count = counts[u8::from(color) as usize][u8::from(kind) as usize];
+                if count > max {
+                    violations.push(Violation::TooMany { color, kind, count });
+                }
+            }
+        }
+        violations
+    }
+
+    /// Checks the position against everything a real game guarantees, reporting every violation.
+    pub fn validate(&self) -> Result<(), Vec<Violation>> {
+        let mut violations = Vec::new();
+        for (i, piece) in self.content.iter().enumerate() {
+            violations.extend(piece_square(*piece, Position::new(i / FILES, i % FILES)));
+        }
+        let counts = self.piece_counts();
+
+        let mut kings_known = true;
+        for color in [PieceColor::Red, PieceColor::Black] {
+            let counts = &counts[u8::from(color) as usize];
+            for (kind, max) in MAX_PIECES {
+                let count = counts[u8::from(kind) as usize];
+                if kind == PieceKind::King {
+                    if count != 1 {
+                        kings_known = false;
+                        violations.push(Violation::KingCount { color, count });
+                    }
+                } else if count > max {
+                    violations.push(Violation::TooMany { color, kind, count });
+                }
+            }
+            let listed = self.king(color);
+            if listed.legal().is_none() || self.get(listed) != Piece::new(PieceKind::King, color) {
+                kings_known = false;
+                violations.push(Violation::KingsMismatch { color, listed });
+            }
+        }
+
+        // Attacks can only be worked out once both kings are where the board says
+        if kings_known {
+            let [red, black] = self.kings;
+            let (low, high) = (red.rank().min(black.rank()), red.rank().max(black.rank()));
+            if red.file() == black.file()
+                && (low + 1..high).all(|rank| self.get(Position::new(rank, red.file())).is_empty())
+            {
+                violations.push(Violation::KingsFacing);
+            } else if self.is_attacked(self.king(self.turn().opposite()), self.turn()) {
+                violations.push(Violation::WaitingSideInCheck);
+            }
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+}
@@ -0,0 +1,381 @@
+use super::*;
+
+/// XQF data past the header is obfuscated with this text, masked by the file keys.
+static COPYRIGHT: &[u8; 32] = b"[(C) Copyright Mr. Dong Shiwei.]";
+
+const HEADER: usize = 0x400;
+/// The newest version written by XQStudio.
+const MAX_VERSION: u8 = 18;
+/// The longest line read, far beyond any real game, which keeps crafted files from exhausting
+/// the stack.
+pub const XQF_MAX_PLIES: usize = 1000;
+
+/// The order of the 32 piece slots in the header, Red first and Black after.
+static XQF_PIECES: [PieceKind; 16] = [
+    PieceKind::Rook,
+    PieceKind::Knight,
+    PieceKind::Bishop,
+    PieceKind::Advisor,
+    PieceKind::King,
+    PieceKind::Advisor,
+    PieceKind::Bishop,
+    PieceKind::Knight,
+    PieceKind::Rook,
+    PieceKind::Cannon,
+    PieceKind::Cannon,
+    PieceKind::Pawn,
+    PieceKind::Pawn,
+    PieceKind::Pawn,
+    PieceKind::Pawn,
+    PieceKind::Pawn,
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XqfError {
+    /// The file does not start with `XQ`.
+    BadSignature,
+    UnsupportedVersion(u8),
+    /// The file ends inside the header or a record, at the given offset.
+    Truncated(usize),
+    /// Two pieces were put on the same square.
+    BadPiece {
+        slot: usize,
+        square: u8,
+    },
+    MissingKing(PieceColor),
+    /// A move that is off the board or illegal, with its ply counted from the start.
+    BadMove {
+        ply: usize,
+        from: u8,
+        to: u8,
+    },
+    /// A comment whose length makes no sense, at the given offset.
+    BadComment(usize),
+    /// A line longer than `XQF_MAX_PLIES`.
+    TooLong,
+    Invalid(Vec<Violation>),
+}
+
+impl std::fmt::Display for XqfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            XqfError::BadSignature => write!(f, "Not an XQF file"),
+            XqfError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported XQF version {}", version)
+            }
+            XqfError::Truncated(offset) => write!(f, "File ends early at offset {}", offset),
+            XqfError::BadPiece { slot, square } => {
+                write!(f, "Piece {} is put on occupied square {}", slot, square)
+            }
+            XqfError::MissingKing(color) => write!(f, "No {:?} king on the board", color),
+            XqfError::BadMove { ply, from, to } => {
+                write!(f, "Bad move from {} to {} at ply {}", from, to, ply)
+            }
+            XqfError::BadComment(offset) => write!(f, "Bad comment length at offset {}", offset),
+            XqfError::TooLong => write!(f, "Line longer than {} plies", XQF_MAX_PLIES),
+            XqfError::Invalid(violations) => write!(f, "Invalid position: {}", join(violations)),
+        }
+    }
+}
+
+impl std::error::Error for XqfError {}
+
+/// A move together with the moves that may follow it, the main line first.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MoveTree {
+    /// `None` at the root, which stands for the starting position.
+    pub mv: Option<Move>,
+    pub comment: Option<String>,
+    pub variations: Vec<MoveTree>,
+}
+
+impl MoveTree {
+    /// The moves of the main line, always following the first variation.
+    pub fn mainline(&self) -> Vec<Move> {
+        let mut result = Vec::new();
+        let mut node = self;
+        while let Some(next) = node.variations.first() {
+            result.extend(next.mv);
+            node = next;
+        }
+        result
+    }
+}
+
+/// A game read from an XQF file.
+#[derive(Debug, Clone)]
+pub struct XqfGame {
+    /// The header tags and the main line.
+    pub record: GameRecord,
+    /// Every variation stored in the file.
+    pub tree: MoveTree,
+}
+
+/// Where `square`, written as `file * 10 + rank` from Red's bottom left, is on the board.
+fn xqf_position(square: u8) -> Option<Position> {
+    (square < 90).then(|| Position::new((square % 10) as usize, (square / 10) as usize))
+}
+
+fn square54_plus221(x: u8) -> u8 {
+    x.wrapping_mul(x).wrapping_mul(54).wrapping_add(221)
+}
+
+struct Keys {
+    xy: u8,
+    xy_from: u8,
+    xy_to: u8,
+    comment: i32,
+    stream: [u8; 32],
+}
+
+impl Keys {
+    fn new(version: u8, tag: &[u8]) -> Self {
+        if version <= 10 {
+            return Self {
+                xy: 0,
+                xy_from: 0,
+                xy_to: 0,
+                comment: 0,
+                stream: [0; 32],
+            };
+        }
+        let xy = square54_plus221(tag[13]).wrapping_mul(tag[13]);
+        let xy_from = square54_plus221(tag[14]).wrapping_mul(xy);
+        let xy_to = square54_plus221(tag[15]).wrapping_mul(xy_from);
+        let sum = tag[12];
+        let mut stream = [0; 32];
+        for (i, key) in stream.iter_mut().enumerate() {
+            *key = COPYRIGHT[i] & ((sum & tag[3]) | tag[8 + i % 4]);
+        }
+        Self {
+            xy,
+            xy_from,
+            xy_to,
+            comment: (sum as i32 * 256 + tag[13] as i32) % 32000 + 767,
+            stream,
+        }
+    }
+}
+
+struct Record {
+    from: u8,
+    to: u8,
+    has_next: bool,
+    has_sibling: bool,
+    comment: Option<String>,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize,
+    version: u8,
+    keys: Keys,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<Vec<u8>, XqfError> {
+        let end = self.offset + len;
+        let raw = self
+            .data
+            .get(self.offset..end)
+            .ok_or(XqfError::Truncated(self.data.len()))?;
+        let result = raw
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| byte.wrapping_sub(self.keys.stream[(self.offset + i) % 32]))
+            .collect();
+        self.offset = end;
+        Ok(result)
+    }
+
+    fn record(&mut self) -> Result<Record, XqfError> {
+        let raw = self.bytes(4)?;
+        let from = raw[0].wrapping_sub(24).wrapping_sub(self.keys.xy_from);
+        let to = raw[1].wrapping_sub(32).wrapping_sub(self.keys.xy_to);
+        let (has_next, has_sibling, has_comment) = if self.version <= 10 {
+            (raw[2] & 0xF0 != 0, raw[2] & 0x0F != 0, true)
+        } else {
+            (raw[2] & 0x80 != 0, raw[2] & 0x40 != 0, raw[2] & 0x20 != 0)
+        };
+
+        let mut comment = None;
+        if has_comment {
+            let offset = self.offset;
+            let len = self.bytes(4)?;
+            let len = i32::from_le_bytes([len[0], len[1], len[2], len[3]]) - self.keys.comment;
+            if len < 0 || self.offset + len as usize > self.data.len() {
+                return Err(XqfError::BadComment(offset));
+            }
+            if len > 0 {
+                let text = self.bytes(len as usize)?;
+                comment = Some(encoding_rs::GBK.decode(&text).0.into_owned());
+            }
+        }
+        Ok(Record {
+            from,
+            to,
+            has_next,
+            has_sibling,
+            comment,
+        })
+    }
+
+    /// Reads a move and the moves branching off beside it, each with its continuations.
+    fn siblings(&mut self, board: &Board, ply: usize) -> Result<Vec<MoveTree>, XqfError> {
+        if ply >= XQF_MAX_PLIES {
+            return Err(XqfError::TooLong);
+        }
+        let mut result = Vec::new();
+        loop {
+            let record = self.record()?;
+            let mv = xqf_position(record.from)
+                .zip(xqf_position(record.to))
+                .map(|(from, to)| Move::new(from, to))
+                .filter(|mv| board.is_legal(*mv))
+                .ok_or(XqfError::BadMove {
+                    ply,
+                    from: record.from,
+                    to: record.to,
+                })?;
+            let mut next = board.clone();
+            next.make_move(mv);
+            let variations = if record.has_next {
+                self.siblings(&next, ply + 1)?
+            } else {
+                Vec::new()
+            };
+            result.push(MoveTree {
+                mv: Some(mv),
+                comment: record.comment,
+                variations,
+            });
+            if !record.has_sibling {
+                return Ok(result);
+            }
+        }
+    }
+}
+
+/// A length-prefixed GBK string from the header.
+fn header_string(header: &[u8], offset: usize, size: usize) -> String {
+    let len = (header[offset] as usize).min(size - 1);
+    let text = encoding_rs::GBK
+        .decode(&header[offset + 1..offset + 1 + len])
+        .0;
+    text.trim().to_string()
+}
+
+impl XqfGame {
+    /// Decodes an XQF file, whether or not it is encrypted.
+    pub fn read(data: &[u8]) -> Result<Self, XqfError> {
+        if !data.starts_with(b"XQ") {
+            return Err(XqfError::BadSignature);
+        }
+        if data.len() < HEADER {
+            return Err(XqfError::Truncated(data.len()));
+        }
+        let version = data[2];
+        if version > MAX_VERSION {
+            return Err(XqfError::UnsupportedVersion(version));
+        }
+        let keys = Keys::new(version, &data[..16]);
+
+        let mut squares = [0u8; 32];
+        for (i, square) in data[0x10..0x30].iter().enumerate() {
+            if version >= 12 {
+                squares[(keys.xy as usize + 1 + i) % 32] = *square;
+            } else {
+                squares[i] = *square;
+            }
+        }
+        let mut content = [Piece::empty(); SQUARES];
+        let mut kings = [None; 2];
+        for (slot, square) in squares.iter().enumerate() {
+            let square = square.wrapping_sub(keys.xy);
+            let Some(pos) = xqf_position(square) else {
+                // Captured, or never on the board
+                continue;
+            };
+            let color = if slot < 16 {
+                PieceColor::Red
+            } else {
+                PieceColor::Black
+            };
+            let kind = XQF_PIECES[slot % 16];
+            let target = &mut content[pos.rank() * FILES + pos.file()];
+            if !target.is_empty() {
+                return Err(XqfError::BadPiece { slot, square });
+            }
+            *target = Piece::new(kind, color);
+            if kind == PieceKind::King {
+                kings[u8::from(color) as usize] = Some(pos);
+            }
+        }
+        let kings = [
+            kings[0].ok_or(XqfError::MissingKing(PieceColor::Red))?,
+            kings[1].ok_or(XqfError::MissingKing(PieceColor::Black))?,
+        ];
+
+        let mut reader = Reader {
+            data,
+            offset: HEADER,
+            version,
+            keys,
+        };
+        let root = reader.record()?;
+        // The file does not say who moves first, but the first move does
+        let turn = if root.has_next {
+            let start = reader.offset;
+            let first = reader.record()?;
+            reader.offset = start;
+            xqf_position(first.from)
+                .and_then(|from| content[from.rank() * FILES + from.file()].color())
+                .unwrap_or(PieceColor::Red)
+        } else {
+            PieceColor::Red
+        };
+        let start = Board::from_parts(content, kings, turn, 0, 1);
+        start.validate().map_err(XqfError::Invalid)?;
+        let tree = MoveTree {
+            mv: None,
+            comment: root.comment,
+            variations: if root.has_next {
+                reader.siblings(&start, 0)?
+            } else {
+                Vec::new()
+            },
+        };
+
+        let mut history = GameHistory::new(start);
+        for mv in tree.mainline() {
+            history.play(mv).unwrap();
+        }
+        let mut record = GameRecord::new(history);
+        for (name, offset, size) in [
+            ("Title", 0x50, 64),
+            ("Event", 0xD0, 64),
+            ("Date", 0x110, 16),
+            ("Site", 0x120, 16),
+            ("Red", 0x130, 16),
+            ("Black", 0x140, 16),
+            ("Annotator", 0x1D0, 16),
+            ("Author", 0x1E0, 16),
+        ] {
+            let value = header_string(data, offset, size);
+            if !value.is_empty() {
+                record.set_tag(name, value);
+            }
+        }
+        record.set_tag(
+            "Result",
+            match data[0x33] {
+                1 => "1-0",
+                2 => "0-1",
+                3 => "1/2-1/2",
+                _ => "*",
+            },
+        );
+
+        Ok(Self { record, tree })
+    }
+}
@@ -0,0 +1,69 @@
+use super::*;
+
+const fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_add(0x9E3779B97F4A7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+    z ^ (z >> 31)
+}
+
+/// One key per square for each of the 8 kinds of each color, fixed at compile time
+/// so that hashes are stable across runs and machines.
+static ZOBRIST_PIECES: [[u64; SQUARES]; 16] = {
+    let mut keys = [[0; SQUARES]; 16];
+    let mut piece = 0;
+    while piece < 16 {
+        let mut square = 0;
+        while square < SQUARES {
+            keys[piece][square] = splitmix64((piece * SQUARES + square + 1) as u64);
+            square += 1;
+        }
+        piece += 1;
+    }
+    keys
+};
+
+/// Mixed in while Black is to move.
+pub(crate) static ZOBRIST_TURN: u64 = splitmix64(0);
+
+pub(crate) fn zobrist_key(piece: Piece, index: usize) -> u64 {
+    match piece.color() {
+        Some(color) => {
+            ZOBRIST_PIECES[(u8::from(color) * 8 + u8::from(piece.kind())) as usize][index]
+        }
+        None => 0,
+    }
+}
+
+impl Board {
+    pub(crate) fn compute_zobrist(&self) -> u64 {
+        let mut result = 0;
+        for (index, piece) in self.content.iter().enumerate() {
+            result ^= zobrist_key(*piece, index);
+        }
+        if self.turn == PieceColor::Black {
+            result ^= ZOBRIST_TURN;
+        }
+        result
+    }
+
+    /// The Zobrist hash of the piece placement and side to move.
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+}
+
+/// Boards are equal when they hold the same position, regardless of the move counters.
+impl PartialEq for Board {
+    fn eq(&self, other: &Self) -> bool {
+        self.zobrist == other.zobrist && self.turn == other.turn && self.content == other.content
+    }
+}
+
+impl Eq for Board {}
+
+impl std::hash::Hash for Board {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        state.write_u64(self.zobrist);
+    }
+}
@@ -0,0 +1,109 @@
+use std::collections::HashSet;
+use xiangqi_core::*;
+
+fn play(moves: &str) -> GameHistory {
+    let mut history = GameHistory::default();
+    for mv in moves.split_whitespace() {
+        history.play(mv.parse().unwrap()).unwrap();
+    }
+    history
+}
+
+#[test]
+fn names_the_opening() {
+    let book = OpeningBook::standard();
+    assert_eq!(book.opening(&play("h2e2 h9g7")), Some("中炮"));
+    assert_eq!(
+        book.opening(&play("h2e2 h9g7 h0g2 b9c7 i0h0")),
+        Some("中炮对屏风马")
+    );
+    assert_eq!(book.opening(&play("g0e2")), Some("飞相局"));
+    assert_eq!(book.opening(&play("c3c4 b7c7")), Some("仙人指路对卒底炮"));
+    assert_eq!(book.opening(&play("a3a4")), None);
+}
+
+#[test]
+fn standard_lines_are_playable() {
+    let book = OpeningBook::standard();
+    let start = book.moves(&Board::default());
+    assert_eq!(start[0].mv, "h2e2".parse().unwrap());
+    assert!(start.len() >= 10);
+    for line in ["h2e2 h9g7 h0g2", "h2e2 b9c7 h0g2", "c0e2", "c3c4"] {
+        assert!(!book.moves(play(line).board()).is_empty(), "{}", line);
+    }
+}
+
+#[test]
+fn picks_vary_by_weight() {
+    let book = OpeningBook::standard();
+    let board = Board::default();
+    let mut seen = HashSet::new();
+    for _ in 0..200 {
+        let mv = book.pick(&board).unwrap();
+        assert!(board.is_legal(mv));
+        seen.insert(mv);
+    }
+    assert!(seen.len() > 3);
+    assert_eq!(book.pick(play("a3a4").board()), None);
+}
+
+#[test]
+fn builds_from_games() {
+    let pgn = "[Result \"1-0\"]\n\n1. h2e2 h9g7 2. h0g2 1-0\n\n\
+               [Result \"0-1\"]\n\n1. h2e2 b9c7 0-1\n\n\
+               [Result \"1/2-1/2\"]\n\n1. c3c4 1/2-1/2\n";
+    let records: Vec<GameRecord> = GameRecord::read_all(pgn)
+        .into_iter()
+        .map(Result::unwrap)
+        .collect();
+    assert_eq!(records.len(), 3);
+
+    let mut book = OpeningBook::new();
+    for record in records.iter() {
+        book.add_game(record, 2);
+    }
+    let start = book.moves(&Board::default());
+    // Won once and lost once, then drawn
+    assert_eq!(start[0].mv, "h2e2".parse().unwrap());
+    assert_eq!(start[0].weight, 4);
+    assert_eq!(start[1].weight, 2);
+    let reply = book.moves(play("h2e2").board());
+    assert_eq!(reply[0].mv, "b9c7".parse().unwrap());
+    assert_eq!(reply[0].weight, 3);
+    assert_eq!(reply[1].weight, 1);
+    // Only the first two plies were taken
+    assert!(book.moves(play("h2e2 h9g7").board()).is_empty());
+}
+
+#[test]
+fn survives_the_binary_format() {
+    let book = OpeningBook::standard();
+    let bytes = book.to_bytes();
+    assert!(bytes.starts_with(b"XQBK"));
+    assert_eq!(OpeningBook::read(&bytes), Ok(book.clone()));
+
+    let mut merged = OpeningBook::with_names();
+    merged.merge(&OpeningBook::read(&bytes).unwrap());
+    assert_eq!(merged, book);
+}
+
+#[test]
+fn rejects_broken_books() {
+    assert_eq!(
+        OpeningBook::read(b"XQF\0\x01"),
+        Err(BookError::BadSignature)
+    );
+    assert_eq!(
+        OpeningBook::read(b"XQBK\x09"),
+        Err(BookError::UnsupportedVersion(9))
+    );
+    let bytes = OpeningBook::standard().to_bytes();
+    assert!(matches!(
+        OpeningBook::read(&bytes[..bytes.len() - 3]),
+        Err(BookError::Truncated(_))
+    ));
+    let mut bad = b"XQBK\x01\x01\0\0\0".to_vec();
+    bad.extend([0; 8]);
+    bad.extend([200, 0]);
+    assert_eq!(OpeningBook::read(&bad), Err(BookError::BadMove(17)));
+}
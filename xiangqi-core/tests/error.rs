@@ -0,0 +1,136 @@
+use xiangqi_core::*;
+
+fn iccs(mv: &str) -> Move {
+    mv.parse().unwrap()
+}
+
+#[test]
+fn bad_characters_are_located() {
+    assert_eq!(
+        Piece::try_from("kx"),
+        Err(Error::BadChar {
+            offset: 1,
+            c: 'x',
+            expected: "color"
+        })
+    );
+    assert_eq!(
+        Position::try_from("39"),
+        Err(Error::BadChar {
+            offset: 1,
+            c: '9',
+            expected: "file"
+        })
+    );
+    assert_eq!(
+        Move::from_iccs("h2-z2"),
+        Err(Error::BadChar {
+            offset: 3,
+            c: 'z',
+            expected: "file"
+        })
+    );
+
+    let mut text = String::from(&Board::default());
+    text.replace_range(6..7, "q");
+    assert_eq!(
+        Board::try_from(text.as_str()),
+        Err(Error::BadChar {
+            offset: 6,
+            c: 'q',
+            expected: "piece kind"
+        })
+    );
+}
+
+#[test]
+fn truncated_input_is_reported() {
+    let text = String::from(&Board::default());
+    assert_eq!(
+        Board::try_from(&text[..text.len() - 1]),
+        Err(Error::UnexpectedEnd {
+            offset: text.len() - 1,
+            expected: "side to move"
+        })
+    );
+    assert_eq!(
+        Board::try_from(&text[..101]),
+        Err(Error::UnexpectedEnd {
+            offset: 101,
+            expected: "piece"
+        })
+    );
+    assert!(matches!(
+        Board::try_from(format!("{}r", text).as_str()),
+        Err(Error::TrailingInput { .. })
+    ));
+    assert_eq!(
+        Position::try_from(String::from("3")),
+        Err(Error::UnexpectedEnd {
+            offset: 1,
+            expected: "file"
+        })
+    );
+}
+
+#[test]
+fn wrong_king_count_is_rejected() {
+    let text = String::from(&Board::default()).replacen("2/", "3/", 1);
+    assert_eq!(
+        Board::try_from(text.as_str()),
+        Err(Error::BadChar {
+            offset: 180,
+            c: '3',
+            expected: "king count of 2"
+        })
+    );
+}
+
+#[test]
+fn illegal_moves_carry_a_reason() {
+    let board = Board::default();
+    let reason = |mv| match board.check_move(iccs(mv)) {
+        Err(Error::IllegalMove { reason, .. }) => Some(reason),
+        _ => None,
+    };
+    assert_eq!(reason("e5e6"), Some(IllegalReason::EmptySquare));
+    assert_eq!(reason("b0b2"), Some(IllegalReason::Unreachable));
+    assert_eq!(
+        board.check_move(iccs("h7e7")),
+        Err(Error::WrongSide {
+            mv: iccs("h7e7"),
+            turn: PieceColor::Red
+        })
+    );
+    assert_eq!(board.check_move(iccs("h2e2")), Ok(()));
+
+    // The black knight screens its king from the red rook
+    let pinned = Board::from_fen("4k4/4n4/9/9/9/9/9/9/4R4/3K5 b").unwrap();
+    assert_eq!(
+        pinned.check_move(iccs("e8c7")),
+        Err(Error::IllegalMove {
+            mv: iccs("e8c7"),
+            reason: IllegalReason::KingInCheck
+        })
+    );
+}
+
+#[test]
+fn history_reports_why_a_move_was_refused() {
+    let mut history = GameHistory::new(Board::default());
+    history.play(iccs("h2e2")).unwrap();
+    assert!(matches!(
+        history.play(iccs("h2e2")),
+        Err(Error::IllegalMove {
+            reason: IllegalReason::EmptySquare,
+            ..
+        })
+    ));
+    assert_eq!(
+        history.board().from_wxf("C5+1"),
+        Err(Error::UnknownMove("C5+1".to_string()))
+    );
+    assert!(Error::UnknownMove("C5+1".to_string())
+        .to_string()
+        .contains("C5+1"));
+}
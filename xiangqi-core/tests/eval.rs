@@ -0,0 +1,179 @@
+use xiangqi_core::*;
+
+fn position(fen: &str) -> Board {
+    Board::from_fen(fen).unwrap()
+}
+
+fn score(fen: &str) -> i32 {
+    position(fen).evaluate(&Weights::default())
+}
+
+/// The same position with the colours swapped and the board turned around.
+fn mirrored(fen: &str) -> String {
+    let mut fields = fen.split(' ');
+    let placement: Vec<String> = fields
+        .next()
+        .unwrap()
+        .split('/')
+        .rev()
+        .map(|rank| {
+            rank.chars()
+                .map(|c| {
+                    if c.is_ascii_uppercase() {
+                        c.to_ascii_lowercase()
+                    } else {
+                        c.to_ascii_uppercase()
+                    }
+                })
+                .collect()
+        })
+        .collect();
+    let turn = if fields.next() == Some("w") { "b" } else { "w" };
+    format!("{} {}", placement.join("/"), turn)
+}
+
+#[test]
+fn start_position_is_balanced() {
+    assert_eq!(Board::default().evaluate(&Weights::default()), 0);
+    let mut board = Board::default();
+    board.next_turn();
+    assert_eq!(board.evaluate(&Weights::default()), 0);
+}
+
+#[test]
+fn colours_are_treated_alike() {
+    for fen in [
+        "2bak4/9/2n1b4/p3p1p1p/6R2/2P6/P3P1P1P/4C1N2/4A4/2B1KAB2 w",
+        "3k5/4a4/4P4/9/2p6/9/9/9/4A4/4K4 b",
+        "rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C2C4/9/RNBAKABNR b",
+    ] {
+        assert_eq!(score(fen), score(&mirrored(fen)), "{}", fen);
+    }
+}
+
+#[test]
+fn material_counts() {
+    let even = "3ak4/9/9/9/9/9/9/9/9/3A1K3 w";
+    let rook_up = "3ak4/9/9/9/9/9/9/9/9/R2A1K3 w";
+    assert!(score(rook_up) - score(even) > 500);
+    assert!(score(&mirrored(rook_up)) - score(even) > 500);
+    // The side to move is the one the score is for
+    assert_eq!(score("3ak4/9/9/9/9/9/9/9/9/R2A1K3 b"), -score(rook_up));
+}
+
+#[test]
+fn crossed_pawns_are_worth_more() {
+    let home = score("3k5/9/9/9/9/9/P8/9/9/4K4 w");
+    let crossed = score("3k5/9/9/9/P8/9/9/9/9/4K4 w");
+    assert!(crossed - home >= Weights::default().river_pawn);
+}
+
+#[test]
+fn king_safety() {
+    let guarded = "3ak4/4a4/9/9/9/9/9/9/4A4/3AK4 w";
+    let bare = "3ak4/4a4/9/9/9/9/9/9/9/4K4 w";
+    let weights = Weights::default();
+    assert!(score(guarded) - score(bare) >= 2 * (weights.material[4] + weights.missing_advisor));
+
+    // A rook down the open king file, against the same rook off to the side
+    let open = "4k4/9/9/9/3r5/9/9/9/9/5K3 w";
+    let closed = "4k4/9/9/9/r8/9/9/9/9/5K3 w";
+    let weights = Weights {
+        tables: [[0; SQUARES]; 8],
+        mobility: [0; 8],
+        ..Weights::default()
+    };
+    assert_eq!(
+        position(closed).evaluate(&weights) - position(open).evaluate(&weights),
+        weights.open_file
+    );
+}
+
+#[test]
+fn mobility_follows_reachable_squares() {
+    let weights = Weights {
+        tables: [[0; SQUARES]; 8],
+        ..Weights::default()
+    };
+    // A cornered knight against a free one
+    let free = position("3k5/9/9/9/9/4N4/9/9/9/4K4 w").evaluate(&weights);
+    let cornered = position("3k5/9/9/9/9/9/9/9/9/N3K4 w").evaluate(&weights);
+    assert_eq!(free - cornered, (8 - 2) * weights.mobility[6]);
+}
+
+#[test]
+fn weights_round_trip_through_config() {
+    let weights = Weights::default();
+    assert_eq!(Weights::parse(&weights.to_config()), Ok(weights));
+
+    let config = format!(
+        "# A heavier rook\nmaterial.rook = 650\nopen_file = 50 # per file\n\ntable.king =\n{}",
+        "1 1 1 1 1 1 1 1 1\n".repeat(RANKS)
+    );
+    let tuned = Weights::parse(&config).unwrap();
+    assert_eq!(tuned.material[7], 650);
+    assert_eq!(tuned.open_file, 50);
+    assert_eq!(tuned.tables[3], [1; SQUARES]);
+    assert_eq!(tuned.tables[7], Weights::default().tables[7]);
+}
+
+#[test]
+fn config_errors_point_at_the_line() {
+    assert_eq!(
+        Weights::parse("material.rook = 600\nmaterial.queen = 900"),
+        Err(ConfigError::UnknownKey {
+            line: 2,
+            key: "material.queen".to_string()
+        })
+    );
+    assert_eq!(
+        Weights::parse("river_pawn = many"),
+        Err(ConfigError::BadValue {
+            line: 1,
+            value: "many".to_string()
+        })
+    );
+    assert_eq!(
+        Weights::parse("table.pawn = 1 2 3\n4 5"),
+        Err(ConfigError::TableLength { line: 1, len: 5 })
+    );
+    assert_eq!(Weights::parse("\n600"), Err(ConfigError::BadLine(2)));
+    assert_eq!(
+        Weights::parse("material.rook = 100000"),
+        Err(ConfigError::BadValue {
+            line: 1,
+            value: "100000".to_string()
+        })
+    );
+    assert!(matches!(
+        Weights::load("/nonexistent/weights.conf"),
+        Err(ConfigError::Io(_))
+    ));
+}
+
+#[test]
+fn extreme_weights_never_look_like_mates() {
+    let path = std::env::temp_dir().join(format!("extreme-{}.conf", std::process::id()));
+    let config: String = ["pawn", "cannon", "advisor", "bishop", "knight", "rook"]
+        .iter()
+        .map(|kind| format!("material.{} = {}\n", kind, MAX_WEIGHT))
+        .collect();
+    std::fs::write(&path, config).unwrap();
+    let weights = Weights::load(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    // A full army against a guarded king is worth far more than a mate with these weights
+    let board = position("3aka3/9/9/9/9/P1P1P1P1P/1C5C1/9/9/RNBAKABNR w");
+    assert!(board.evaluate(&weights) > MATE);
+    let mut searcher = Searcher::new(1);
+    searcher.set_weights(weights);
+    let mut scores = Vec::new();
+    searcher.search(&board, SearchLimits::depth(2), |info| {
+        scores.push(info.score)
+    });
+    assert!(
+        scores.iter().all(|score| score.abs() <= MAX_EVAL),
+        "{:?}",
+        scores
+    );
+}
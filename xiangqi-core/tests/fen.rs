@@ -0,0 +1,81 @@
+use xiangqi_core::*;
+
+#[test]
+fn starting_position_round_trips() {
+    assert_eq!(Board::default().to_fen(), START_FEN);
+    assert_eq!(Board::from_fen(START_FEN).unwrap().to_fen(), START_FEN);
+}
+
+#[test]
+fn counters_follow_the_game() {
+    let mut board = Board::default();
+    // Cannon takes the knight
+    board.force(Position::new(2, 1), Position::new(9, 1));
+    board.next_turn();
+    assert_eq!(
+        board.to_fen(),
+        "rCbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/7C1/9/RNBAKABNR b - - 0 1"
+    );
+    board.force(Position::new(9, 0), Position::new(8, 0));
+    board.next_turn();
+    assert_eq!(
+        board.to_fen(),
+        "1Cbakabnr/r8/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/7C1/9/RNBAKABNR w - - 1 2"
+    );
+}
+
+#[test]
+fn optional_fields_take_defaults() {
+    let board = Board::from_fen("4k4/9/9/9/9/9/9/9/9/3K5 b").unwrap();
+    assert_eq!(board.turn(), PieceColor::Black);
+    assert_eq!(board.halfmove(), 0);
+    assert_eq!(board.fullmove(), 1);
+    assert_eq!(board.king(PieceColor::Red), Position::new(0, 3));
+    assert_eq!(board.king(PieceColor::Black), Position::new(9, 4));
+    assert_eq!(board.to_fen(), "4k4/9/9/9/9/9/9/9/9/3K5 b - - 0 1");
+}
+
+#[test]
+fn alternative_piece_letters_are_accepted() {
+    let board = Board::from_fen("3k5/9/9/9/9/9/9/9/9/2EHK4 w - - 0 1").unwrap();
+    assert!(board.get(Position::new(0, 2)).is_kind(PieceKind::Bishop));
+    assert!(board.get(Position::new(0, 3)).is_kind(PieceKind::Knight));
+}
+
+fn error(fen: &str) -> FenError {
+    Board::from_fen(fen).err().unwrap()
+}
+
+#[test]
+fn errors_are_precise() {
+    assert_eq!(error(""), FenError::MissingField("placement"));
+    assert_eq!(
+        error("4k4/9/9/9/9/9/9/9/9/4K4"),
+        FenError::MissingField("side to move")
+    );
+    assert_eq!(error("4k4/9/9/9/9/9/9/9/4K4 w"), FenError::RankCount(9));
+    assert_eq!(
+        error("4k4/9/9/9/8/9/9/9/9/4K4 w"),
+        FenError::RankLength { rank: 4, files: 8 }
+    );
+    assert_eq!(
+        error("4k4/9/9/9/9/9/9/9/9/4K3X w"),
+        FenError::BadPiece { offset: 23, c: 'X' }
+    );
+    assert_eq!(
+        error("4k4/9/9/9/9/9/9/9/9/4K4 x"),
+        FenError::BadTurn("x".to_string())
+    );
+    assert_eq!(
+        error("4k4/9/9/9/9/9/9/9/9/4K4 w - - x 1"),
+        FenError::BadCounter("x".to_string())
+    );
+    assert_eq!(
+        error("9/9/9/9/9/9/9/9/9/4K4 w"),
+        FenError::MissingKing(PieceColor::Black)
+    );
+    assert_eq!(
+        error("4k4/9/9/9/9/9/9/9/9/4K4 w - - 0 1 x"),
+        FenError::TrailingField("x".to_string())
+    );
+}
@@ -0,0 +1,65 @@
+use xiangqi_core::*;
+
+fn mv(from: (usize, usize), to: (usize, usize)) -> Move {
+    Move::new(Position::new(from.0, from.1), Position::new(to.0, to.1))
+}
+
+#[test]
+fn unmake_restores_a_capture() {
+    let mut board = Board::default();
+    let before = board.to_fen();
+    // Cannon takes the knight
+    let undo = board.make_move(mv((2, 1), (9, 1)));
+    assert!(undo.captured.is_kind(PieceKind::Knight));
+    assert!(undo.captured.is_color(PieceColor::Black));
+    assert_eq!(undo.turn, PieceColor::Red);
+    assert_eq!(board.turn(), PieceColor::Black);
+    board.unmake_move(undo);
+    assert_eq!(board.to_fen(), before);
+}
+
+#[test]
+fn unmake_restores_the_king() {
+    let mut board = Board::from_fen("4k4/9/9/9/9/9/9/9/9/3K5 w - - 7 12").unwrap();
+    let before = board.to_fen();
+    let undo = board.make_move(mv((0, 3), (1, 3)));
+    assert_eq!(board.king(PieceColor::Red), Position::new(1, 3));
+    assert_eq!(undo.kings[0], Position::new(0, 3));
+    board.unmake_move(undo);
+    assert_eq!(board.king(PieceColor::Red), Position::new(0, 3));
+    assert_eq!(board.to_fen(), before);
+}
+
+#[test]
+fn unmake_every_legal_move() {
+    let mut board = Board::default();
+    board.make_move(mv((2, 7), (2, 4)));
+    board.make_move(mv((9, 7), (7, 6)));
+    let before = board.to_fen();
+    for legal in board.legal_moves() {
+        let undo = board.make_move(legal);
+        board.unmake_move(undo);
+        assert_eq!(board.to_fen(), before, "after {:?}", legal);
+    }
+}
+
+#[test]
+fn history_takes_back_and_replays() {
+    let mut history = GameHistory::default();
+    history.play(mv((2, 7), (2, 4))).unwrap();
+    history.play(mv((9, 7), (7, 6))).unwrap();
+    assert!(history.play(mv((0, 0), (5, 0))).is_err());
+    assert_eq!(history.len(), 2);
+
+    let positions: Vec<String> = history.replay().map(|board| board.to_fen()).collect();
+    assert_eq!(positions.len(), 3);
+    assert_eq!(positions[0], START_FEN);
+    assert_eq!(positions[2], history.board().to_fen());
+
+    assert_eq!(history.take_back(), Some(mv((9, 7), (7, 6))));
+    assert_eq!(history.board().to_fen(), positions[1]);
+    assert_eq!(history.take_back(), Some(mv((2, 7), (2, 4))));
+    assert_eq!(history.board().to_fen(), START_FEN);
+    assert_eq!(history.take_back(), None);
+    assert!(history.is_empty());
+}
@@ -0,0 +1,44 @@
+use xiangqi_core::*;
+
+#[test]
+fn squares_are_named_from_reds_side() {
+    assert_eq!(Position::new(0, 0).to_iccs(), "a0");
+    assert_eq!(Position::new(9, 8).to_iccs(), "i9");
+    assert_eq!(Position::new(2, 7).to_iccs(), "h2");
+    assert_eq!(Position::from_iccs("e9").unwrap(), Position::new(9, 4));
+    assert_eq!(Position::from_iccs("E9").unwrap(), Position::new(9, 4));
+}
+
+#[test]
+fn central_cannon_opening() {
+    let mv: Move = "h2e2".parse().unwrap();
+    assert_eq!(mv, Move::new(Position::new(2, 7), Position::new(2, 4)));
+    assert_eq!(mv.to_string(), "h2e2");
+    assert!(Board::default().is_legal(mv));
+    assert_eq!(Move::from_iccs("H2-E2").unwrap(), mv);
+}
+
+#[test]
+fn black_moves_are_absolute() {
+    // Black's answering central cannon, seen from Black it mirrors Red's move
+    let mv: Move = "b7e7".parse().unwrap();
+    let mut board = Board::default();
+    board.make_move("h2e2".parse().unwrap());
+    assert!(board.is_legal(mv));
+    assert_eq!(mv.flipped().to_string(), "h2e2");
+    assert_eq!(Position::new(0, 0).flipped(), Position::new(9, 8));
+}
+
+#[test]
+fn every_legal_move_round_trips() {
+    for mv in Board::default().legal_moves() {
+        assert_eq!(mv.to_string().parse::<Move>().unwrap(), mv);
+    }
+}
+
+#[test]
+fn bad_notation_is_rejected() {
+    for bad in ["", "h2", "h2e", "j2e2", "h2e10", "h2+e2", "h2e2e"] {
+        assert!(bad.parse::<Move>().is_err(), "{:?}", bad);
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::HashSet;
+use xiangqi_core::*;
+
+#[test]
+fn levels_grow_stronger() {
+    for pair in Level::ALL.windows(2) {
+        let (weaker, stronger) = (pair[0], pair[1]);
+        assert!(weaker.limits().depth <= stronger.limits().depth);
+        assert!(weaker.noise() >= stronger.noise());
+        assert!(weaker.blunder_chance() >= stronger.blunder_chance());
+    }
+    assert_eq!(Level::Master.noise(), 0);
+    assert_eq!(Level::Master.blunder_chance(), 0);
+    assert_eq!(Level::default(), Level::Intermediate);
+}
+
+#[test]
+fn weak_levels_vary_their_play() {
+    let board = Board::default();
+    let mut searcher = Searcher::new(1);
+    let mut seen = HashSet::new();
+    for _ in 0..20 {
+        let mv = Level::Beginner
+            .choose(&mut searcher, &board, |_| {})
+            .unwrap();
+        assert!(board.is_legal(mv));
+        seen.insert(mv);
+    }
+    assert!(seen.len() > 1);
+}
+
+#[test]
+fn noise_does_not_miss_mates() {
+    let board = Board::from_fen("4k4/R8/1R7/9/9/9/9/9/9/3K5 w").unwrap();
+    let mut searcher = Searcher::new(1);
+    searcher.set_noise(Level::Novice.noise());
+    let mv = searcher.search(&board, SearchLimits::depth(2), |_| {});
+    assert_eq!(mv, Some("b7b9".parse().unwrap()));
+}
+
+#[test]
+fn styles_change_the_weights() {
+    assert_eq!(PlayStyle::Balanced.weights(), Weights::default());
+    let aggressive = PlayStyle::Aggressive.weights();
+    let defensive = PlayStyle::Defensive.weights();
+    assert!(aggressive.river_pawn > defensive.river_pawn);
+    assert!(aggressive.missing_advisor < defensive.missing_advisor);
+    assert!(aggressive.open_file < defensive.open_file);
+    // Material stays the same, so no style gives pieces away
+    assert_eq!(aggressive.material, defensive.material);
+}
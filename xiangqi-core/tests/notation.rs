@@ -0,0 +1,126 @@
+use xiangqi_core::*;
+
+fn mv(iccs: &str) -> Move {
+    iccs.parse().unwrap()
+}
+
+fn position(fen: &str) -> Board {
+    Board::from_fen(fen).unwrap()
+}
+
+#[test]
+fn central_cannon() {
+    let board = Board::default();
+    assert_eq!(board.to_chinese(mv("h2e2")), "炮二平五");
+    assert_eq!(board.to_wxf(mv("h2e2")), "C2=5");
+    assert_eq!(board.from_chinese("炮二平五").unwrap(), mv("h2e2"));
+    assert_eq!(board.from_wxf("C2=5").unwrap(), mv("h2e2"));
+    assert_eq!(board.from_wxf("c2.5").unwrap(), mv("h2e2"));
+}
+
+#[test]
+fn red_files_run_right_to_left() {
+    let board = Board::default();
+    assert_eq!(board.to_chinese(mv("h0g2")), "马二进三");
+    assert_eq!(board.to_chinese(mv("a0a1")), "车九进一");
+    assert_eq!(board.to_chinese(mv("g0e2")), "相三进五");
+    assert_eq!(board.to_chinese(mv("f0e1")), "仕四进五");
+    assert_eq!(board.to_chinese(mv("e0e1")), "帅五进一");
+    assert_eq!(board.to_chinese(mv("c3c4")), "兵七进一");
+    assert_eq!(board.to_wxf(mv("h0g2")), "H2+3");
+    assert_eq!(board.to_wxf(mv("b2b9")), "C8+7");
+    assert_eq!(board.to_wxf(mv("g0e2")), "E3+5");
+    assert_eq!(board.from_wxf("B3+5").unwrap(), mv("g0e2"));
+}
+
+#[test]
+fn black_files_use_arabic_numerals() {
+    let mut board = Board::default();
+    board.make_move(mv("h2e2"));
+    assert_eq!(board.to_chinese(mv("h9g7")), "马8进7");
+    assert_eq!(board.to_chinese(mv("b7e7")), "炮2平5");
+    assert_eq!(board.to_chinese(mv("c6c5")), "卒3进1");
+    assert_eq!(board.to_wxf(mv("h9g7")), "H8+7");
+    assert_eq!(board.from_chinese("马8进7").unwrap(), mv("h9g7"));
+    // Traditional characters and full-width or Chinese numerals are read too
+    assert_eq!(board.from_chinese("馬８進７").unwrap(), mv("h9g7"));
+    assert_eq!(board.from_chinese("马八进七").unwrap(), mv("h9g7"));
+    assert_eq!(board.from_wxf("H8+7").unwrap(), mv("h9g7"));
+    assert_eq!(board.from_wxf("N8+7").unwrap(), mv("h9g7"));
+}
+
+#[test]
+fn backward_moves_count_ranks() {
+    let mut board = Board::default();
+    board.make_move(mv("b2b4"));
+    board.make_move(mv("h7h5"));
+    assert_eq!(board.to_chinese(mv("b4b1")), "炮八退三");
+    assert_eq!(board.to_wxf(mv("b4b1")), "C8-3");
+}
+
+#[test]
+fn tandem_pieces() {
+    let board = position("5k3/9/9/9/9/4R4/9/4R4/9/3K5 w");
+    assert_eq!(board.to_chinese(mv("e4e8")), "前车进四");
+    assert_eq!(board.to_chinese(mv("e2c2")), "后车平七");
+    assert_eq!(board.to_wxf(mv("e4e8")), "R++4");
+    assert_eq!(board.to_wxf(mv("e2c2")), "R-=7");
+    assert_eq!(board.from_chinese("後車平七").unwrap(), mv("e2c2"));
+    assert_eq!(board.from_wxf("+R+4").unwrap(), mv("e4e8"));
+
+    // Black's front piece is the one nearer to Red
+    let board = position("3k5/9/2c6/9/2c6/9/9/9/9/4K4 b");
+    assert_eq!(board.to_chinese(mv("c5d5")), "前炮平4");
+    assert_eq!(board.to_chinese(mv("c7c9")), "后炮退2");
+}
+
+#[test]
+fn stacked_pawns() {
+    let board = position("3k5/9/9/2P6/2P6/2P6/9/9/9/4K4 w");
+    assert_eq!(board.to_chinese(mv("c6c7")), "前兵进一");
+    assert_eq!(board.to_chinese(mv("c5d5")), "中兵平六");
+    assert_eq!(board.to_chinese(mv("c4b4")), "后兵平八");
+    assert_eq!(board.to_wxf(mv("c5d5")), "Pb=6");
+
+    // With stacks on two files the file replaces the piece name
+    let board = position("3k5/9/9/2P3P2/2P3P2/9/9/9/9/4K4 w");
+    assert_eq!(board.to_chinese(mv("c6c7")), "前七进一");
+    assert_eq!(board.to_chinese(mv("g5f5")), "后三平四");
+    assert_eq!(board.to_wxf(mv("c6c7")), "P+7+1");
+    assert_eq!(board.from_chinese("后三平四").unwrap(), mv("g5f5"));
+}
+
+#[test]
+fn advisors_keep_their_file() {
+    let board = position("3k5/9/9/9/9/9/9/3A5/9/3AK4 w");
+    assert_eq!(board.to_chinese(mv("d2e1")), "仕六退五");
+    assert_eq!(board.to_wxf(mv("d0e1")), "A6+5");
+}
+
+#[test]
+fn every_move_round_trips() {
+    let mut board = Board::default();
+    for ply in 0..60 {
+        let moves = board.legal_moves();
+        if moves.is_empty() {
+            break;
+        }
+        for legal in moves.iter() {
+            assert_eq!(board.from_wxf(&board.to_wxf(*legal)).unwrap(), *legal);
+            assert_eq!(
+                board.from_chinese(&board.to_chinese(*legal)).unwrap(),
+                *legal
+            );
+        }
+        board.make_move(moves[ply * 11 % moves.len()]);
+    }
+}
+
+#[test]
+fn unknown_moves_are_rejected() {
+    let board = Board::default();
+    assert!(board.from_chinese("炮二平四").is_ok());
+    assert!(board.from_chinese("炮二进五").is_err());
+    assert!(board.from_wxf("C2=2").is_err());
+    assert!(board.from_wxf("").is_err());
+}
@@ -0,0 +1,91 @@
+use xiangqi_core::*;
+
+fn mv(from: (usize, usize), to: (usize, usize)) -> Move {
+    Move::new(Position::new(from.0, from.1), Position::new(to.0, to.1))
+}
+
+fn outcome(fen: &str) -> Option<GameOutcome> {
+    Board::from_fen(fen).unwrap().outcome(&GameRules::default())
+}
+
+#[test]
+fn start_is_not_over() {
+    assert_eq!(Board::default().outcome(&GameRules::default()), None);
+}
+
+#[test]
+fn checkmate_and_stalemate_lose() {
+    // Two rooks mate the king on the back rank
+    assert_eq!(
+        outcome("R2k5/R8/9/9/9/9/9/9/9/5K3 b - - 0 1"),
+        Some(GameOutcome::RedWins(WinReason::Checkmate))
+    );
+    // The rook and the red king leave Black without a move, but not in check
+    assert_eq!(
+        outcome("3k5/R8/9/9/9/9/9/9/9/4K4 b - - 0 1"),
+        Some(GameOutcome::RedWins(WinReason::Stalemate))
+    );
+}
+
+#[test]
+fn move_limit_draws() {
+    let fen = "4k4/9/9/9/9/9/9/9/R8/3K5 w - - 119 80";
+    assert_eq!(outcome(fen), None);
+
+    let mut board = Board::from_fen(fen).unwrap();
+    board.make_move(mv((1, 0), (1, 1)));
+    assert_eq!(
+        board.outcome(&GameRules::default()),
+        Some(GameOutcome::Draw(DrawReason::MoveLimit))
+    );
+    let unlimited = GameRules {
+        move_limit: None,
+        ..Default::default()
+    };
+    assert_eq!(board.outcome(&unlimited), None);
+}
+
+#[test]
+fn defensive_pieces_cannot_mate() {
+    assert_eq!(
+        outcome("4k4/9/9/9/9/9/9/9/9/3K5 w - - 0 1"),
+        Some(GameOutcome::Draw(DrawReason::InsufficientMaterial))
+    );
+    assert_eq!(
+        outcome("2bakab2/9/9/9/9/9/9/9/4A4/2B1K1B2 w - - 0 1"),
+        Some(GameOutcome::Draw(DrawReason::InsufficientMaterial))
+    );
+    // A single pawn can still mate
+    assert_eq!(outcome("4k4/9/9/9/9/9/P8/9/9/3K5 w - - 0 1"), None);
+}
+
+#[test]
+fn history_reports_repetition() {
+    let mut history = GameHistory::new(Board::default());
+    let cycle = [
+        mv((0, 0), (1, 0)),
+        mv((9, 0), (8, 0)),
+        mv((1, 0), (0, 0)),
+        mv((8, 0), (9, 0)),
+    ];
+    for mv in cycle.iter().cycle().take(8) {
+        assert_eq!(history.outcome(&GameRules::default()), None);
+        history.play(*mv).unwrap();
+    }
+    assert_eq!(
+        history.outcome(&GameRules::default()),
+        Some(GameOutcome::Draw(DrawReason::Repetition))
+    );
+}
+
+#[test]
+fn outcomes_read_well() {
+    assert_eq!(
+        GameOutcome::BlackWins(WinReason::Checkmate).to_string(),
+        "Black wins by checkmate"
+    );
+    assert_eq!(
+        GameOutcome::Draw(DrawReason::Agreement).to_string(),
+        "Draw by agreement"
+    );
+}
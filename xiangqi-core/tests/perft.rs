@@ -0,0 +1,23 @@
+use xiangqi_core::*;
+
+#[test]
+fn perft_starting_position() {
+    let board = Board::default();
+    assert_eq!(board.perft(0), 1);
+    assert_eq!(board.perft(1), 44);
+    assert_eq!(board.perft(2), 1_920);
+    assert_eq!(board.perft(3), 79_666);
+}
+
+#[test]
+fn perft_starting_position_depth_4() {
+    assert_eq!(Board::default().perft(4), 3_290_240);
+}
+
+#[test]
+fn perft_divide_sums_to_perft() {
+    let board = Board::default();
+    let divided = board.perft_divide(2);
+    assert_eq!(divided.len(), 44);
+    assert_eq!(divided.iter().map(|(_, nodes)| nodes).sum::<u64>(), 1_920);
+}
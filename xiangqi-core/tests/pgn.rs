@@ -0,0 +1,122 @@
+use xiangqi_core::*;
+
+fn play(moves: &[&str]) -> GameHistory {
+    let mut history = GameHistory::default();
+    for mv in moves {
+        history.play(mv.parse().unwrap()).unwrap();
+    }
+    history
+}
+
+#[test]
+fn export_writes_tags_and_numbered_moves() {
+    let mut record = GameRecord::new(play(&["h2e2", "h9g7", "h0g2", "i9h9"]));
+    record.set_tag("Red", "Alice");
+    record.set_tag("Black", "Bob");
+    record.set_tag("Result", "*");
+    assert_eq!(
+        record.to_pgn(MoveFormat::Iccs),
+        "[Game \"Chinese Chess\"]\n\
+         [Red \"Alice\"]\n\
+         [Black \"Bob\"]\n\
+         [Result \"*\"]\n\
+         [Format \"ICCS\"]\n\
+         \n\
+         1. h2e2 h9g7 2. h0g2 i9h9 *\n"
+    );
+    assert!(record
+        .to_pgn(MoveFormat::Chinese)
+        .contains("1. 炮二平五 马8进7 2. 马二进三 车9平8 *"));
+    assert!(record
+        .to_pgn(MoveFormat::Wxf)
+        .contains("1. C2=5 H8+7 2. H2+3 R9=8 *"));
+}
+
+#[test]
+fn every_format_round_trips() {
+    let history = play(&["h2e2", "h9g7", "h0g2", "i9h9", "i0h0", "b7b3", "e3e4"]);
+    let mut record = GameRecord::new(history.clone());
+    record.set_tag("Event", "Club \"Open\"");
+    for format in [MoveFormat::Iccs, MoveFormat::Wxf, MoveFormat::Chinese] {
+        let read = GameRecord::from_pgn(&record.to_pgn(format)).unwrap();
+        assert_eq!(
+            read.history.moves().collect::<Vec<_>>(),
+            history.moves().collect::<Vec<_>>()
+        );
+        assert_eq!(read.tag("Event"), Some("Club \"Open\""));
+        assert_eq!(read.tag("Format"), Some(format.name()));
+    }
+}
+
+#[test]
+fn custom_start_is_kept() {
+    let fen = "4k4/9/9/9/9/9/9/9/4R4/3K5 b - - 0 30";
+    let mut history = GameHistory::new(Board::from_fen(fen).unwrap());
+    history.play("e9f9".parse().unwrap()).unwrap();
+    history.play("e1f1".parse().unwrap()).unwrap();
+    let pgn = GameRecord::new(history).to_pgn(MoveFormat::Iccs);
+    assert!(pgn.contains(&format!("[FEN \"{}\"]", fen)));
+    assert!(pgn.contains("30... e9f9 31. e1f1 *"));
+
+    let read = GameRecord::from_pgn(&pgn).unwrap();
+    assert_eq!(read.history.start().to_fen(), fen);
+    assert_eq!(read.history.len(), 2);
+}
+
+#[test]
+fn import_skips_annotations_and_guesses_the_notation() {
+    let pgn = r#"[Game "Chinese Chess"]
+[Event "Casual"]
+[Result "1-0"]
+
+{ The most popular opening }
+1. 炮二平五 $1 马8进7 (1... 炮8平5 2. 马二进三) 2.N2+3 ; knights out
+R9=8 3. i0h0 1-0
+"#;
+    let record = GameRecord::from_pgn(pgn).unwrap();
+    assert_eq!(record.tag("Result"), Some("1-0"));
+    let moves: Vec<String> = record.history.moves().map(|mv| mv.to_string()).collect();
+    assert_eq!(moves, ["h2e2", "h9g7", "h0g2", "i9h9", "i0h0"]);
+}
+
+#[test]
+fn errors_point_at_the_problem() {
+    let err = GameRecord::from_pgn("1. h2e2 h2e2").unwrap_err();
+    assert_eq!(
+        err,
+        PgnError::BadMove {
+            ply: 1,
+            text: "h2e2".to_string()
+        }
+    );
+    assert!(matches!(
+        GameRecord::from_pgn("[Event Casual]\n1. h2e2"),
+        Err(PgnError::BadTag(_))
+    ));
+    assert!(matches!(
+        GameRecord::from_pgn("[FEN \"9/9 w\"]\n"),
+        Err(PgnError::Fen(FenError::RankCount(2)))
+    ));
+    assert_eq!(
+        GameRecord::from_pgn("1. h2e2 { unfinished").unwrap_err(),
+        PgnError::Unterminated('}')
+    );
+    // A stray closing bracket must not hide the moves after it
+    assert_eq!(
+        GameRecord::from_pgn("1. h2e2 ) h9g7 2. h0g2").unwrap_err(),
+        PgnError::Unmatched(')')
+    );
+}
+
+#[test]
+fn results_follow_the_outcome() {
+    assert_eq!(
+        pgn_result(Some(GameOutcome::RedWins(WinReason::Checkmate))),
+        "1-0"
+    );
+    assert_eq!(
+        pgn_result(Some(GameOutcome::Draw(DrawReason::Agreement))),
+        "1/2-1/2"
+    );
+    assert_eq!(pgn_result(None), "*");
+}
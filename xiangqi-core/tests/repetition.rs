@@ -0,0 +1,116 @@
+use xiangqi_core::*;
+
+fn mv(from: (usize, usize), to: (usize, usize)) -> Move {
+    Move::new(Position::new(from.0, from.1), Position::new(to.0, to.1))
+}
+
+/// Plays `cycle` over and over, returning the judgement after each move.
+fn repeat(fen: &str, cycle: &[Move], rules: RuleSet) -> Vec<Option<Repetition>> {
+    let mut history = GameHistory::new(Board::from_fen(fen).unwrap());
+    let mut result = Vec::new();
+    for mv in cycle.iter().cycle().take(cycle.len() * (REPETITIONS - 1)) {
+        history.play(*mv).unwrap();
+        result.push(history.repetition(rules));
+    }
+    result
+}
+
+fn judge(fen: &str, cycle: &[Move], rules: RuleSet) -> Repetition {
+    let judgements = repeat(fen, cycle, rules);
+    let (last, rest) = judgements.split_last().unwrap();
+    assert!(rest.iter().all(Option::is_none));
+    last.unwrap()
+}
+
+#[test]
+fn idle_repetition_is_a_draw() {
+    let cycle = [
+        mv((0, 0), (1, 0)),
+        mv((9, 0), (8, 0)),
+        mv((1, 0), (0, 0)),
+        mv((8, 0), (9, 0)),
+    ];
+    let repetition = judge(START_FEN, &cycle, RuleSet::Asian);
+    assert_eq!(repetition.red, Pattern::Idle);
+    assert_eq!(repetition.black, Pattern::Idle);
+    assert_eq!(repetition.verdict(), Verdict::Draw);
+}
+
+#[test]
+fn perpetual_check_loses() {
+    // The rook keeps checking the king from either side
+    let fen = "4k4/9/9/9/3R5/9/9/9/9/5K3 w";
+    let cycle = [
+        mv((5, 3), (5, 4)),
+        mv((9, 4), (9, 3)),
+        mv((5, 4), (5, 3)),
+        mv((9, 3), (9, 4)),
+    ];
+    let repetition = judge(fen, &cycle, RuleSet::Asian);
+    assert_eq!(repetition.red, Pattern::Check);
+    assert_eq!(repetition.black, Pattern::Idle);
+    assert_eq!(repetition.verdict(), Verdict::Loses(PieceColor::Red));
+}
+
+#[test]
+fn perpetual_chase_loses() {
+    // The rook keeps attacking an unprotected cannon
+    let fen = "5k3/9/1c7/9/9/9/2R6/9/9/3K5 w";
+    let cycle = [
+        mv((3, 2), (3, 1)),
+        mv((7, 1), (7, 2)),
+        mv((3, 1), (3, 2)),
+        mv((7, 2), (7, 1)),
+    ];
+    let repetition = judge(fen, &cycle, RuleSet::Asian);
+    assert_eq!(repetition.red, Pattern::Chase);
+    assert_eq!(repetition.black, Pattern::Idle);
+    assert_eq!(repetition.verdict(), Verdict::Loses(PieceColor::Red));
+}
+
+#[test]
+fn attacking_a_protected_piece_is_not_a_chase() {
+    // The black rook guards the cannon along the rank
+    let fen = "5k3/9/1c6r/9/9/9/2R6/9/9/3K5 w";
+    let cycle = [
+        mv((3, 2), (3, 1)),
+        mv((7, 1), (7, 2)),
+        mv((3, 1), (3, 2)),
+        mv((7, 2), (7, 1)),
+    ];
+    let repetition = judge(fen, &cycle, RuleSet::Asian);
+    assert_eq!(repetition.red, Pattern::Idle);
+    assert_eq!(repetition.verdict(), Verdict::Draw);
+}
+
+#[test]
+fn chasing_a_crossed_pawn_depends_on_the_rules() {
+    let fen = "3k5/9/9/9/9/1p7/9/2R6/9/4K4 w";
+    let cycle = [
+        mv((2, 2), (2, 1)),
+        mv((4, 1), (4, 2)),
+        mv((2, 1), (2, 2)),
+        mv((4, 2), (4, 1)),
+    ];
+    let asian = judge(fen, &cycle, RuleSet::Asian);
+    assert_eq!(asian.red, Pattern::Idle);
+    assert_eq!(asian.verdict(), Verdict::Draw);
+
+    let crossed = judge(fen, &cycle, RuleSet::CrossedPawns);
+    assert_eq!(crossed.red, Pattern::Chase);
+    assert_eq!(crossed.verdict(), Verdict::Loses(PieceColor::Red));
+}
+
+#[test]
+fn verdict_prefers_check_over_chase() {
+    let check_chase = Repetition {
+        red: Pattern::Check,
+        black: Pattern::Chase,
+    };
+    assert_eq!(check_chase.verdict(), Verdict::Loses(PieceColor::Red));
+    let both = Repetition {
+        red: Pattern::Chase,
+        black: Pattern::Chase,
+    };
+    assert_eq!(both.verdict(), Verdict::Draw);
+}
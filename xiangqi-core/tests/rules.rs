@@ -0,0 +1,445 @@
+use std::collections::HashSet;
+use xiangqi_core::*;
+
+use PieceColor::{Black, Red};
+use PieceKind::*;
+
+/// Builds an unchecked board from a list of `(rank, file, kind, color)` placements.
+fn unchecked(
+    pieces: &[(usize, usize, PieceKind, PieceColor)],
+    turn: PieceColor,
+) -> Result<Board, Error> {
+    let mut grid = vec![vec![Piece::empty(); FILES]; RANKS];
+    let mut kings = [Position::new(0, 4), Position::new(9, 4)];
+    for &(rank, file, kind, color) in pieces {
+        grid[rank][file] = Piece::new(kind, color);
+        if kind == King {
+            kings[Into::<u8>::into(color) as usize] = Position::new(rank, file);
+        }
+    }
+    let mut s = String::new();
+    for rank in grid {
+        for piece in rank {
+            s.push_str(&String::from(piece));
+        }
+    }
+    s.push_str("2/");
+    for king in kings {
+        s.push_str(&String::from(king));
+    }
+    s.push(turn.into());
+    Board::parse_unchecked(&s)
+}
+
+fn board(pieces: &[(usize, usize, PieceKind, PieceColor)], turn: PieceColor) -> Board {
+    unchecked(pieces, turn).unwrap()
+}
+
+fn reachable(board: &Board, rank: usize, file: usize) -> HashSet<Position> {
+    board.reachable(Position::new(rank, file))
+}
+
+fn squares(list: &[(usize, usize)]) -> HashSet<Position> {
+    list.iter()
+        .map(|&(rank, file)| Position::new(rank, file))
+        .collect()
+}
+
+#[test]
+fn king_reaches_left_palace_column() {
+    let board = board(&[(1, 3, King, Red), (9, 5, King, Black)], Red);
+    assert_eq!(reachable(&board, 1, 3), squares(&[(0, 3), (2, 3), (1, 4)]));
+}
+
+#[test]
+fn king_stays_inside_right_palace_column() {
+    let board = board(&[(1, 5, King, Red), (9, 3, King, Black)], Red);
+    assert_eq!(reachable(&board, 1, 5), squares(&[(0, 5), (2, 5), (1, 4)]));
+}
+
+#[test]
+fn black_king_stays_inside_palace() {
+    let board = board(&[(0, 5, King, Red), (7, 3, King, Black)], Black);
+    assert_eq!(reachable(&board, 7, 3), squares(&[(8, 3), (7, 4)]));
+}
+
+#[test]
+fn kings_face_each_other() {
+    let open = board(&[(0, 4, King, Red), (9, 4, King, Black)], Red);
+    assert!(reachable(&open, 0, 4).contains(&Position::new(9, 4)));
+    assert!(open.is_check());
+
+    let blocked = board(
+        &[(0, 4, King, Red), (5, 4, Pawn, Red), (9, 4, King, Black)],
+        Red,
+    );
+    assert!(!reachable(&blocked, 0, 4).contains(&Position::new(9, 4)));
+    assert!(!blocked.is_check());
+}
+
+#[test]
+fn king_cannot_expose_itself_to_the_other_king() {
+    let board = board(&[(0, 3, King, Red), (9, 4, King, Black)], Red);
+    let mut next = board.clone();
+    next.force(Position::new(0, 3), Position::new(0, 4));
+    assert!(next.is_check());
+}
+
+#[test]
+fn advisor_moves_diagonally_in_palace() {
+    let center = board(
+        &[(1, 4, Advisor, Red), (0, 3, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(reachable(&center, 1, 4), squares(&[(0, 5), (2, 3), (2, 5)]));
+
+    let corner = board(
+        &[(0, 3, Advisor, Red), (0, 4, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(reachable(&corner, 0, 3), squares(&[(1, 4)]));
+
+    let black = board(
+        &[
+            (0, 3, King, Red),
+            (7, 5, Advisor, Black),
+            (9, 4, King, Black),
+        ],
+        Black,
+    );
+    assert_eq!(reachable(&black, 7, 5), squares(&[(8, 4)]));
+}
+
+#[test]
+fn bishop_moves_two_diagonally() {
+    let board = board(
+        &[(2, 4, Bishop, Red), (0, 3, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(
+        reachable(&board, 2, 4),
+        squares(&[(0, 2), (0, 6), (4, 2), (4, 6)])
+    );
+}
+
+#[test]
+fn bishop_is_blocked_by_its_eye() {
+    let board = board(
+        &[
+            (0, 2, Bishop, Red),
+            (1, 3, Advisor, Red),
+            (0, 4, King, Red),
+            (9, 5, King, Black),
+        ],
+        Red,
+    );
+    assert_eq!(reachable(&board, 0, 2), squares(&[(2, 0)]));
+}
+
+#[test]
+fn bishop_cannot_cross_the_river() {
+    let red = board(
+        &[(4, 2, Bishop, Red), (0, 4, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(reachable(&red, 4, 2), squares(&[(2, 0), (2, 4)]));
+
+    let black = board(
+        &[
+            (0, 3, King, Red),
+            (5, 6, Bishop, Black),
+            (9, 4, King, Black),
+        ],
+        Black,
+    );
+    assert_eq!(reachable(&black, 5, 6), squares(&[(7, 4), (7, 8)]));
+}
+
+#[test]
+fn knight_moves_in_all_directions() {
+    let board = board(
+        &[(4, 4, Knight, Red), (0, 3, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(
+        reachable(&board, 4, 4),
+        squares(&[
+            (6, 3),
+            (6, 5),
+            (2, 3),
+            (2, 5),
+            (3, 2),
+            (5, 2),
+            (3, 6),
+            (5, 6)
+        ])
+    );
+}
+
+#[test]
+fn knight_is_blocked_by_its_leg() {
+    let board = board(
+        &[
+            (4, 4, Knight, Red),
+            (4, 5, Pawn, Black),
+            (5, 4, Pawn, Red),
+            (0, 3, King, Red),
+            (9, 5, King, Black),
+        ],
+        Red,
+    );
+    assert_eq!(
+        reachable(&board, 4, 4),
+        squares(&[(2, 3), (2, 5), (3, 2), (5, 2)])
+    );
+}
+
+#[test]
+fn knight_captures_but_does_not_land_on_allies() {
+    let board = board(
+        &[
+            (4, 4, Knight, Red),
+            (6, 3, Rook, Black),
+            (6, 5, Rook, Red),
+            (0, 3, King, Red),
+            (9, 5, King, Black),
+        ],
+        Red,
+    );
+    let reachable = reachable(&board, 4, 4);
+    assert!(reachable.contains(&Position::new(6, 3)));
+    assert!(!reachable.contains(&Position::new(6, 5)));
+}
+
+#[test]
+fn pawn_only_advances_before_the_river() {
+    let red = board(
+        &[(3, 0, Pawn, Red), (0, 4, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(reachable(&red, 3, 0), squares(&[(4, 0)]));
+
+    let black = board(
+        &[(0, 3, King, Red), (6, 4, Pawn, Black), (9, 4, King, Black)],
+        Black,
+    );
+    assert_eq!(reachable(&black, 6, 4), squares(&[(5, 4)]));
+}
+
+#[test]
+fn pawn_moves_sideways_after_the_river() {
+    let red = board(
+        &[(5, 4, Pawn, Red), (0, 3, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(reachable(&red, 5, 4), squares(&[(6, 4), (5, 3), (5, 5)]));
+
+    let black = board(
+        &[(0, 3, King, Red), (4, 4, Pawn, Black), (9, 5, King, Black)],
+        Black,
+    );
+    assert_eq!(reachable(&black, 4, 4), squares(&[(3, 4), (4, 3), (4, 5)]));
+}
+
+#[test]
+fn pawn_on_the_last_rank_only_moves_sideways() {
+    let board = board(
+        &[(9, 0, Pawn, Red), (0, 3, King, Red), (9, 5, King, Black)],
+        Red,
+    );
+    assert_eq!(reachable(&board, 9, 0), squares(&[(9, 1)]));
+}
+
+#[test]
+fn cannon_needs_exactly_one_screen_to_capture() {
+    let board = board(
+        &[
+            (4, 0, Cannon, Red),
+            (4, 3, Pawn, Red),
+            (4, 5, Pawn, Black),
+            (4, 7, Rook, Black),
+            (6, 0, Knight, Black),
+            (0, 3, King, Red),
+            (9, 5, King, Black),
+        ],
+        Red,
+    );
+    let reachable = reachable(&board, 4, 0);
+    assert_eq!(
+        reachable,
+        squares(&[
+            (4, 1),
+            (4, 2),
+            (4, 5),
+            (5, 0),
+            (3, 0),
+            (2, 0),
+            (1, 0),
+            (0, 0)
+        ])
+    );
+    // Neither the screen, the first piece behind it, nor anything further is a target
+    assert!(!reachable.contains(&Position::new(4, 3)));
+    assert!(!reachable.contains(&Position::new(4, 7)));
+    assert!(!reachable.contains(&Position::new(6, 0)));
+}
+
+#[test]
+fn cannon_does_not_capture_allies_behind_a_screen() {
+    let board = board(
+        &[
+            (0, 0, Cannon, Red),
+            (2, 0, Pawn, Black),
+            (5, 0, Rook, Red),
+            (0, 3, King, Red),
+            (9, 5, King, Black),
+        ],
+        Red,
+    );
+    assert_eq!(reachable(&board, 0, 0), squares(&[(1, 0), (0, 1), (0, 2)]));
+}
+
+#[test]
+fn cannon_gives_check_through_a_screen() {
+    let board = board(
+        &[
+            (0, 4, King, Red),
+            (3, 4, Pawn, Red),
+            (7, 4, Cannon, Black),
+            (9, 3, King, Black),
+        ],
+        Red,
+    );
+    assert!(board.is_check());
+}
+
+#[test]
+fn rook_slides_until_blocked() {
+    let board = board(
+        &[
+            (4, 4, Rook, Red),
+            (4, 6, Pawn, Red),
+            (7, 4, Knight, Black),
+            (0, 3, King, Red),
+            (9, 5, King, Black),
+        ],
+        Red,
+    );
+    assert_eq!(
+        reachable(&board, 4, 4),
+        squares(&[
+            (4, 0),
+            (4, 1),
+            (4, 2),
+            (4, 3),
+            (4, 5),
+            (5, 4),
+            (6, 4),
+            (7, 4),
+            (3, 4),
+            (2, 4),
+            (1, 4),
+            (0, 4)
+        ])
+    );
+}
+
+#[test]
+fn starting_position_has_44_moves() {
+    let mut board = Board::default();
+    assert_eq!(board.legal_moves().len(), 44);
+    board.next_turn();
+    assert_eq!(board.legal_moves().len(), 44);
+}
+
+#[test]
+fn pinned_pieces_cannot_move_off_the_line() {
+    let board = board(
+        &[
+            (0, 4, King, Red),
+            (3, 4, Rook, Red),
+            (8, 4, Rook, Black),
+            (9, 3, King, Black),
+        ],
+        Red,
+    );
+    let pinned = Position::new(3, 4);
+    assert!(board.is_legal(Move::new(pinned, Position::new(5, 4))));
+    assert!(board.is_legal(Move::new(pinned, Position::new(8, 4))));
+    assert!(!board.is_legal(Move::new(pinned, Position::new(3, 5))));
+    assert!(board
+        .legal_moves()
+        .iter()
+        .filter(|mv| mv.from == pinned)
+        .all(|mv| mv.to.file() == 4));
+}
+
+#[test]
+fn only_the_side_to_move_has_legal_moves() {
+    let board = Board::default();
+    assert!(board
+        .legal_moves()
+        .iter()
+        .all(|mv| board.get(mv.from).is_color(PieceColor::Red)));
+    assert!(!board.is_legal(Move::new(Position::new(6, 0), Position::new(5, 0))));
+    assert!(board.is_legal(Move::new(Position::new(3, 0), Position::new(4, 0))));
+    assert!(!board.is_legal(Move::new(Position::new(3, 0), Position::new_int(10, 0))));
+}
+
+#[test]
+fn checkmate_has_no_legal_moves() {
+    // Two rooks on adjacent files against a bare king
+    let board = board(
+        &[
+            (0, 3, King, Red),
+            (8, 0, Rook, Red),
+            (9, 1, Rook, Red),
+            (9, 4, King, Black),
+        ],
+        Black,
+    );
+    assert!(board.is_check());
+    assert!(board.legal_moves().is_empty());
+}
+
+#[test]
+fn dense_boards_fit_the_move_list() {
+    // A full set, spread out so that every piece has room to move
+    let mut pieces = vec![
+        (1, 4, King, Red),
+        (0, 3, Advisor, Red),
+        (2, 3, Advisor, Red),
+        (2, 0, Bishop, Red),
+        (2, 8, Bishop, Red),
+        (4, 2, Knight, Red),
+        (4, 6, Knight, Red),
+        (3, 1, Rook, Red),
+        (5, 7, Rook, Red),
+        (6, 4, Cannon, Red),
+        (7, 0, Cannon, Red),
+        (9, 4, King, Black),
+    ];
+    for file in [0, 2, 4, 6, 8] {
+        pieces.push((8, file, Pawn, Red));
+    }
+    let board = board(&pieces, Red);
+    let mut moves = MoveList::new();
+    board.pseudo_moves(&mut moves);
+    assert!(moves.len() > 80 && moves.len() <= MAX_MOVES);
+    moves.clear();
+    board.legal_moves_into(&mut moves);
+    assert!(moves.len() <= MAX_MOVES);
+}
+
+#[test]
+fn unchecked_boards_have_at_most_a_full_set() {
+    let rooks = [(0, 0, Rook, Red), (0, 8, Rook, Red), (5, 4, Rook, Red)];
+    assert_eq!(
+        unchecked(&rooks, Red).unwrap_err(),
+        Error::InvalidPosition(vec![Violation::TooMany {
+            color: Red,
+            kind: Rook,
+            count: 3
+        }])
+    );
+}
@@ -0,0 +1,129 @@
+use std::time::{Duration, Instant};
+use xiangqi_core::*;
+
+fn position(fen: &str) -> Board {
+    Board::from_fen(fen).unwrap()
+}
+
+fn best(board: &Board, depth: u32) -> (Option<Move>, Vec<SearchInfo>) {
+    let mut infos = Vec::new();
+    let mv = Searcher::new(1).search(board, SearchLimits::depth(depth), |info| {
+        infos.push(info.clone())
+    });
+    (mv, infos)
+}
+
+#[test]
+fn finds_mate_in_one() {
+    let (mv, infos) = best(&position("4k4/R8/1R7/9/9/9/9/9/9/3K5 w"), 4);
+    assert_eq!(mv, Some("b7b9".parse().unwrap()));
+    let last = infos.last().unwrap();
+    assert_eq!(last.score, MATE - 1);
+    // A mate stops the deepening early
+    assert_eq!(last.depth, 1);
+}
+
+#[test]
+fn sees_mate_in_two() {
+    // The rook has to take the rank first, then the other rook mates
+    let board = position("4k4/9/9/9/9/9/9/9/R8/1R1K5 w");
+    let (_, infos) = best(&board, 4);
+    assert_eq!(infos.last().unwrap().score, MATE - 3);
+    assert_eq!(infos.last().unwrap().pv.len(), 3);
+}
+
+#[test]
+fn wins_free_material() {
+    let (mv, _) = best(&position("r3k4/9/9/9/9/9/9/9/9/R2K5 w"), 3);
+    assert_eq!(mv, Some("a0a9".parse().unwrap()));
+}
+
+#[test]
+fn mated_side_has_no_move() {
+    let (mv, infos) = best(&position("1R2k4/R8/9/9/9/9/9/9/9/3K5 b"), 3);
+    assert_eq!(mv, None);
+    assert!(infos.is_empty());
+}
+
+#[test]
+fn iterations_deepen_and_stay_legal() {
+    let board = Board::default();
+    let (mv, infos) = best(&board, 4);
+    assert!(board.is_legal(mv.unwrap()));
+    assert_eq!(
+        infos.iter().map(|info| info.depth).collect::<Vec<_>>(),
+        vec![1, 2, 3, 4]
+    );
+    for info in infos {
+        let mut line = board.clone();
+        for mv in info.pv {
+            assert!(line.is_legal(mv));
+            line.make_move(mv);
+        }
+    }
+}
+
+#[test]
+fn limits_are_respected() {
+    let board = Board::default();
+    let mut searcher = Searcher::default();
+    let limits = SearchLimits {
+        nodes: Some(5_000),
+        ..Default::default()
+    };
+    let mut nodes = 0;
+    let mv = searcher.search(&board, limits, |info| nodes = info.nodes);
+    assert!(board.is_legal(mv.unwrap()));
+    assert!(nodes <= 5_000);
+
+    let start = Instant::now();
+    let mv = searcher.search(
+        &board,
+        SearchLimits::time(Duration::from_millis(100)),
+        |_| {},
+    );
+    assert!(board.is_legal(mv.unwrap()));
+    assert!(start.elapsed() < Duration::from_secs(2));
+}
+
+#[test]
+fn stop_flag_ends_the_search() {
+    let mut searcher = Searcher::default();
+    let stop = searcher.stop_flag();
+    let board = Board::default();
+    let mv = searcher.search(&board, SearchLimits::default(), |info| {
+        if info.depth == 2 {
+            stop.store(true, std::sync::atomic::Ordering::Relaxed);
+        }
+    });
+    assert!(board.is_legal(mv.unwrap()));
+}
+
+#[test]
+fn threads_share_the_work() {
+    let mut searcher = Searcher::new(4);
+    searcher.set_threads(4);
+    assert_eq!(searcher.threads(), 4);
+    let board = position("4k4/R8/1R7/9/9/9/9/9/9/3K5 w");
+    let mv = searcher.search(&board, SearchLimits::depth(4), |_| {});
+    assert_eq!(mv, Some("b7b9".parse().unwrap()));
+
+    let board = Board::default();
+    let mv = searcher.search(
+        &board,
+        SearchLimits::time(Duration::from_millis(200)),
+        |_| {},
+    );
+    assert!(board.is_legal(mv.unwrap()));
+    searcher.set_threads(1);
+    assert_eq!(searcher.threads(), 1);
+}
+
+#[test]
+fn perpetual_check_loses() {
+    // Red can only hold off the mate by checking forever, which the rules punish
+    let board = position("3k5/4a4/3a5/9/R8/9/7r1/9/8r/4K4 w");
+    let (_, infos) = best(&board, 6);
+    let score = infos.last().unwrap().score;
+    assert!(score < -MATE_BOUND, "{}", score);
+}
@@ -0,0 +1,195 @@
+#![cfg(unix)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::PathBuf;
+use std::time::Duration;
+use xiangqi_core::*;
+
+/// Writes an engine that answers from a script and logs every command it gets.
+fn mock_engine(name: &str) -> (PathBuf, PathBuf) {
+    let dir = std::env::temp_dir().join(format!("xiangqi-ucci-{}-{}", std::process::id(), name));
+    std::fs::create_dir_all(&dir).unwrap();
+    let script = dir.join("engine.sh");
+    let log = dir.join("commands.log");
+    std::fs::write(
+        &script,
+        format!(
+            r#"#!/bin/sh
+while read -r line; do
+    echo "$line" >> "{}"
+    case "$line" in
+        ucci) echo "id name Mock"; echo "option batch type check default false"; echo ucciok ;;
+        uci) echo "id name Mock"; echo "id author Nobody"; echo uciok ;;
+        isready) echo readyok ;;
+        "go depth 1") echo "nobestmove" ;;
+        go*)
+            echo "info depth 1 score 12 pv h2e2"
+            echo "info depth 2 score mate 3 nodes 42 time 5 pv h2e2 h9g7"
+            echo "bestmove h2e2 ponder h9g7" ;;
+        quit) echo bye; exit 0 ;;
+    esac
+done
+"#,
+            log.display()
+        ),
+    )
+    .unwrap();
+    std::fs::set_permissions(&script, std::fs::Permissions::from_mode(0o755)).unwrap();
+    (script, log)
+}
+
+fn commands(log: &PathBuf) -> Vec<String> {
+    std::fs::read_to_string(log)
+        .unwrap()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn parses_engine_output() {
+    assert_eq!(EngineMessage::parse("readyok"), EngineMessage::ReadyOk);
+    assert_eq!(
+        EngineMessage::parse("id name Eleeye 3.2"),
+        EngineMessage::Id {
+            key: "name".to_string(),
+            value: "Eleeye 3.2".to_string()
+        }
+    );
+    assert_eq!(
+        EngineMessage::parse("bestmove (none)"),
+        EngineMessage::NoBestMove
+    );
+    let EngineMessage::Info(info) =
+        EngineMessage::parse("info depth 9 seldepth 12 score cp -35 nodes 1000 pv b0c2 h9g7")
+    else {
+        panic!("not an info line");
+    };
+    assert_eq!(info.depth, Some(9));
+    assert_eq!(info.score, Some(Score::Cp(-35)));
+    assert_eq!(info.nodes, Some(1000));
+    assert_eq!(info.pv.len(), 2);
+
+    let EngineMessage::Info(info) = EngineMessage::parse("info string book move") else {
+        panic!("not an info line");
+    };
+    assert_eq!(info.string.as_deref(), Some("book move"));
+}
+
+#[test]
+fn plays_through_ucci() {
+    let (script, log) = mock_engine("ucci");
+    let mut engine = ExternalEngine::launch(&script, Protocol::Ucci).unwrap();
+    assert_eq!(engine.name(), Some("Mock"));
+    assert_eq!(engine.options, ["batch type check default false"]);
+    engine.wait_ready().unwrap();
+
+    let mut history = GameHistory::new(Board::default());
+    history.play("h2e2".parse().unwrap()).unwrap();
+    engine.history(&history).unwrap();
+    engine
+        .go(&SearchLimits::time(Duration::from_secs(2)))
+        .unwrap();
+    let mut infos = Vec::new();
+    let mv = engine
+        .best_move(Duration::from_secs(10), |info| infos.push(info.clone()))
+        .unwrap();
+    assert_eq!(mv, Some("h2e2".parse().unwrap()));
+    assert_eq!(infos.len(), 2);
+    assert_eq!(infos[0].score, Some(Score::Cp(12)));
+    assert_eq!(infos[1].score, Some(Score::Mate(3)));
+    assert_eq!(infos[1].time, Some(Duration::from_millis(5)));
+
+    engine.go(&SearchLimits::depth(1)).unwrap();
+    assert_eq!(engine.best_move(Duration::from_secs(10), |_| {}), Ok(None));
+    engine.set_option("batch", "true").unwrap();
+    engine.quit();
+
+    assert_eq!(
+        commands(&log),
+        [
+            "ucci".to_string(),
+            "isready".to_string(),
+            format!("position fen {} moves h2e2", Board::default().to_fen()),
+            "go time 2 movestogo 1".to_string(),
+            "go depth 1".to_string(),
+            "setoption batch true".to_string(),
+            "quit".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn speaks_uci_dialect() {
+    let (script, log) = mock_engine("uci");
+    let mut engine = ExternalEngine::launch(&script, Protocol::Uci).unwrap();
+    assert_eq!(engine.ids.len(), 2);
+    engine.set_option("Hash", "64").unwrap();
+    engine.position(&Board::default(), &[]).unwrap();
+    engine
+        .go(&SearchLimits {
+            nodes: Some(5000),
+            time: Some(Duration::from_millis(300)),
+            ..Default::default()
+        })
+        .unwrap();
+    assert!(engine.best_move(Duration::from_secs(10), |_| {}).is_ok());
+    engine.go(&SearchLimits::default()).unwrap();
+    engine.stop().unwrap();
+    engine.quit();
+
+    assert_eq!(
+        commands(&log),
+        [
+            "uci".to_string(),
+            "setoption name Hash value 64".to_string(),
+            format!("position fen {}", Board::default().to_fen()),
+            "go nodes 5000 movetime 300".to_string(),
+            "go infinite".to_string(),
+            "stop".to_string(),
+            "quit".to_string(),
+        ]
+    );
+}
+
+#[test]
+fn reports_missing_engine() {
+    let result = ExternalEngine::launch("/nonexistent/engine", Protocol::Ucci);
+    assert!(matches!(result, Err(EngineError::Spawn(_))));
+}
+
+#[test]
+fn writes_search_info() {
+    let info = EngineInfo::from(&SearchInfo {
+        depth: 3,
+        score: MATE - 3,
+        nodes: 100,
+        time: Duration::from_millis(7),
+        pv: vec!["b9b7".parse().unwrap()],
+    });
+    assert_eq!(info.score, Some(Score::Mate(2)));
+    assert_eq!(
+        info.to_line(Protocol::Uci),
+        "info depth 3 score mate 2 nodes 100 time 7 pv b9b7"
+    );
+    assert_eq!(
+        EngineMessage::parse(&info.to_line(Protocol::Uci)),
+        EngineMessage::Info(info.clone())
+    );
+    // UCCI mates are plain scores, which only read as mates on the engine's own scale
+    let EngineMessage::Info(ucci) = EngineMessage::parse(&info.to_line(Protocol::Ucci)) else {
+        panic!("not an info line");
+    };
+    assert_eq!(ucci.score, Some(Score::Cp(MATE - 2)));
+    assert_eq!(ucci.score.unwrap().with_mate_score(MATE), Score::Mate(2));
+}
+
+#[test]
+fn mate_scores_follow_the_engine() {
+    // ElephantEye mates at 10000, where this crate's scale would still be centipawns
+    assert_eq!(Score::Cp(9_995).with_mate_score(10_000), Score::Mate(5));
+    assert_eq!(Score::Cp(-9_998).with_mate_score(10_000), Score::Mate(-2));
+    assert_eq!(Score::Cp(9_995).with_mate_score(MATE), Score::Cp(9_995));
+    assert_eq!(Score::Cp(120).with_mate_score(10_000), Score::Cp(120));
+    assert_eq!(Score::Mate(3).with_mate_score(10_000), Score::Mate(3));
+}
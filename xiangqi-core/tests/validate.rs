@@ -0,0 +1,109 @@
+use xiangqi_core::*;
+
+fn violations(fen: &str) -> Vec<Violation> {
+    match Board::from_fen(fen) {
+        Err(FenError::Invalid(violations)) => violations,
+        other => panic!("{:?} was not rejected: {:?}", fen, other),
+    }
+}
+
+fn pos(iccs: &str) -> Position {
+    Position::from_iccs(iccs).unwrap()
+}
+
+#[test]
+fn real_positions_are_valid() {
+    assert_eq!(Board::default().validate(), Ok(()));
+    let mut board = Board::default();
+    for mv in [
+        "h2e2", "h9g7", "h0g2", "i9h9", "e3e4", "g6g5", "e4e5", "g5g4",
+    ] {
+        board.make_move(mv.parse().unwrap());
+        assert_eq!(board.validate(), Ok(()), "after {}", mv);
+    }
+    // Crossed pawns may go sideways, kings may leave the back rank
+    assert!(Board::from_fen("3k5/4P4/9/9/2p6/9/9/9/4A4/5K3 w").is_ok());
+}
+
+#[test]
+fn every_violation_is_reported() {
+    // Three kings, an elephant in the middle of the board, a red pawn at home
+    let found = violations("4k4/9/9/9/9/4B4/9/9/P8/3K1K3 w");
+    assert!(found.contains(&Violation::KingCount {
+        color: PieceColor::Red,
+        count: 2
+    }));
+    assert!(found.contains(&Violation::BishopSquare(pos("e4"))));
+    assert!(found.contains(&Violation::PawnSquare(pos("a1"))));
+}
+
+#[test]
+fn pieces_stay_in_their_areas() {
+    assert_eq!(
+        violations("4k4/9/9/9/9/9/9/9/9/K8 w"),
+        vec![Violation::OutsidePalace(pos("a0"))]
+    );
+    // The centre of the palace is an advisor square
+    assert!(Board::from_fen("3k5/9/9/9/9/9/9/9/4A4/4K4 w").is_ok());
+}
+
+#[test]
+fn impossible_squares_are_caught() {
+    assert_eq!(
+        violations("3k5/3a5/9/9/9/9/9/9/9/3K5 w"),
+        vec![Violation::AdvisorSquare(pos("d8"))]
+    );
+    assert_eq!(
+        violations("3k5/9/9/9/9/9/1P7/9/9/4K4 w"),
+        vec![Violation::PawnSquare(pos("b3"))]
+    );
+    assert_eq!(
+        violations("3k5/9/9/9/9/9/9/9/9/4K3B w"),
+        vec![Violation::BishopSquare(pos("i0"))]
+    );
+    assert_eq!(
+        violations("3k5/9/9/9/9/9/9/9/9/RRR1K4 w"),
+        vec![Violation::TooMany {
+            color: PieceColor::Red,
+            kind: PieceKind::Rook,
+            count: 3
+        }]
+    );
+}
+
+#[test]
+fn kings_must_be_safe_for_the_side_that_moved() {
+    assert_eq!(
+        violations("4k4/9/9/9/9/9/9/9/9/4K4 w"),
+        vec![Violation::KingsFacing]
+    );
+    // Red to move, but Black's king is attacked by the red rook
+    assert_eq!(
+        violations("3k5/9/9/9/9/9/9/9/9/3RK4 w"),
+        vec![Violation::WaitingSideInCheck]
+    );
+    assert!(Board::from_fen("3k5/9/9/9/9/9/9/9/9/3RK4 b").is_ok());
+}
+
+#[test]
+fn listed_kings_must_match_the_pieces() {
+    let text = String::from(&Board::default());
+    // The kings are listed after the king count as rank and file digits
+    let moved = text.replacen("2/0494", "2/0394", 1);
+    assert_eq!(
+        Board::try_from(moved.as_str()),
+        Err(Error::InvalidPosition(vec![Violation::KingsMismatch {
+            color: PieceColor::Red,
+            listed: pos("d0")
+        }]))
+    );
+}
+
+#[test]
+fn imported_records_are_validated() {
+    let pgn = "[FEN \"4k4/9/9/9/9/9/9/9/9/4K4 w - - 0 1\"]\n\n*\n";
+    assert!(matches!(
+        GameRecord::from_pgn(pgn),
+        Err(PgnError::Fen(FenError::Invalid(_)))
+    ));
+}
@@ -0,0 +1,210 @@
+use xiangqi_core::*;
+
+static START: [u8; 32] = [
+    0, 10, 20, 30, 40, 50, 60, 70, 80, 12, 72, 3, 23, 43, 63, 83, // Red
+    9, 19, 29, 39, 49, 59, 69, 79, 89, 17, 77, 6, 26, 46, 66, 86, // Black
+];
+
+static COPYRIGHT: &[u8; 32] = b"[(C) Copyright Mr. Dong Shiwei.]";
+
+struct Node<'a> {
+    from: u8,
+    to: u8,
+    next: bool,
+    sibling: bool,
+    comment: &'a str,
+}
+
+fn node(from: u8, to: u8, next: bool, sibling: bool, comment: &str) -> Node<'_> {
+    Node {
+        from,
+        to,
+        next,
+        sibling,
+        comment,
+    }
+}
+
+fn square54_plus221(x: u8) -> u8 {
+    x.wrapping_mul(x).wrapping_mul(54).wrapping_add(221)
+}
+
+/// Writes an XQF file the way XQStudio does, encrypted from version 11 on.
+fn encode(version: u8, squares: &[u8; 32], nodes: &[Node], red: &str) -> Vec<u8> {
+    let mut data = vec![0u8; 0x400];
+    data[..3].copy_from_slice(&[b'X', b'Q', version]);
+    let (mut xy, mut xy_from, mut xy_to, mut comment_key) = (0u8, 0u8, 0u8, 0i32);
+    let mut stream = [0u8; 32];
+    if version > 10 {
+        let tag = [
+            0x5A, 0, 0, 0, 0, 0, 0, 0, 0x13, 0x57, 0x9B, 0xDF, 0xA5, 0x3C, 0x71, 0xE8,
+        ];
+        data[3..16].copy_from_slice(&tag[3..]);
+        xy = square54_plus221(tag[13]).wrapping_mul(tag[13]);
+        xy_from = square54_plus221(tag[14]).wrapping_mul(xy);
+        xy_to = square54_plus221(tag[15]).wrapping_mul(xy_from);
+        comment_key = (tag[12] as i32 * 256 + tag[13] as i32) % 32000 + 767;
+        for (i, key) in stream.iter_mut().enumerate() {
+            *key = COPYRIGHT[i] & ((tag[12] & tag[3]) | tag[8 + i % 4]);
+        }
+    }
+    for i in 0..32 {
+        data[0x10 + i] = if version >= 12 {
+            squares[(xy as usize + 1 + i) % 32].wrapping_add(xy)
+        } else {
+            squares[i].wrapping_add(xy)
+        };
+    }
+    data[0x33] = 1;
+    let red = encoding_rs::GBK.encode(red).0;
+    data[0x130] = red.len() as u8;
+    data[0x131..0x131 + red.len()].copy_from_slice(&red);
+
+    let mut plain = Vec::new();
+    for node in nodes {
+        let flags = if version > 10 {
+            (node.next as u8) << 7
+                | (node.sibling as u8) << 6
+                | (!node.comment.is_empty() as u8) << 5
+        } else {
+            ((node.next as u8) * 0xF0) | ((node.sibling as u8) * 0x0F)
+        };
+        plain.extend([
+            node.from.wrapping_add(24).wrapping_add(xy_from),
+            node.to.wrapping_add(32).wrapping_add(xy_to),
+            flags,
+            0,
+        ]);
+        if version <= 10 || !node.comment.is_empty() {
+            let comment = encoding_rs::GBK.encode(node.comment).0;
+            plain.extend((comment.len() as i32 + comment_key).to_le_bytes());
+            plain.extend(comment.iter());
+        }
+    }
+    for (i, byte) in plain.into_iter().enumerate() {
+        data.push(byte.wrapping_add(stream[(0x400 + i) % 32]));
+    }
+    data
+}
+
+/// The central cannon, answered by the left knight or else the right knight.
+fn opening() -> Vec<Node<'static>> {
+    vec![
+        node(0, 0, true, false, "开局"),
+        node(72, 42, true, false, ""),
+        node(79, 67, false, true, "中炮对屏风马"),
+        node(19, 27, false, false, ""),
+    ]
+}
+
+fn check_opening(game: &XqfGame) {
+    assert_eq!(game.record.tag("Red"), Some("许银川"));
+    assert_eq!(game.record.tag("Result"), Some("1-0"));
+    let moves: Vec<String> = game
+        .record
+        .history
+        .moves()
+        .map(|mv| mv.to_string())
+        .collect();
+    assert_eq!(moves, ["h2e2", "h9g7"]);
+
+    let tree = &game.tree;
+    assert_eq!(tree.comment.as_deref(), Some("开局"));
+    let cannon = &tree.variations[0];
+    assert_eq!(cannon.mv.unwrap().to_string(), "h2e2");
+    let replies: Vec<String> = cannon
+        .variations
+        .iter()
+        .map(|reply| reply.mv.unwrap().to_string())
+        .collect();
+    assert_eq!(replies, ["h9g7", "b9c7"]);
+    assert_eq!(
+        cannon.variations[0].comment.as_deref(),
+        Some("中炮对屏风马")
+    );
+    assert_eq!(cannon.variations[1].comment, None);
+}
+
+#[test]
+fn plain_files() {
+    let data = encode(10, &START, &opening(), "许银川");
+    check_opening(&XqfGame::read(&data).unwrap());
+}
+
+#[test]
+fn encrypted_files() {
+    for version in [11, 12, 18] {
+        let data = encode(version, &START, &opening(), "许银川");
+        check_opening(&XqfGame::read(&data).unwrap());
+    }
+}
+
+#[test]
+fn endgames_start_from_their_position() {
+    // Kings and a black rook, with Black moving first
+    let mut squares = [0xFF; 32];
+    squares[4] = 40;
+    squares[20] = 39;
+    squares[24] = 85;
+    let nodes = [node(0, 0, true, false, ""), node(85, 81, false, false, "")];
+    let game = XqfGame::read(&encode(12, &squares, &nodes, "")).unwrap();
+    let start = game.record.history.start();
+    assert_eq!(start.to_fen(), "3k5/9/9/9/8r/9/9/9/9/4K4 b - - 0 1");
+    assert_eq!(game.record.history.len(), 1);
+    assert_eq!(game.record.tag("Red"), None);
+}
+
+#[test]
+fn errors_are_clear() {
+    assert_eq!(
+        XqfGame::read(b"PK\x03\x04").unwrap_err(),
+        XqfError::BadSignature
+    );
+    assert_eq!(
+        XqfGame::read(b"XQ\x0a").unwrap_err(),
+        XqfError::Truncated(3)
+    );
+
+    let mut data = encode(12, &START, &opening(), "");
+    data[2] = 30;
+    assert_eq!(
+        XqfGame::read(&data).unwrap_err(),
+        XqfError::UnsupportedVersion(30)
+    );
+
+    let mut squares = START;
+    squares[4] = 0xFF;
+    assert_eq!(
+        XqfGame::read(&encode(12, &squares, &opening(), "")).unwrap_err(),
+        XqfError::MissingKing(PieceColor::Red)
+    );
+
+    // The rook cannot jump over the knight
+    let nodes = [node(0, 0, true, false, ""), node(0, 20, false, false, "")];
+    assert_eq!(
+        XqfGame::read(&encode(12, &START, &nodes, "")).unwrap_err(),
+        XqfError::BadMove {
+            ply: 0,
+            from: 0,
+            to: 20
+        }
+    );
+
+    // Knights going back and forth for longer than any game
+    let mut nodes = vec![node(0, 0, true, false, "")];
+    for ply in 0..=XQF_MAX_PLIES {
+        let (from, to) = [(70, 62), (79, 67), (62, 70), (67, 79)][ply % 4];
+        nodes.push(node(from, to, ply < XQF_MAX_PLIES, false, ""));
+    }
+    assert_eq!(
+        XqfGame::read(&encode(12, &START, &nodes, "")).unwrap_err(),
+        XqfError::TooLong
+    );
+
+    let mut data = encode(12, &START, &opening(), "");
+    data.truncate(data.len() - 2);
+    assert!(matches!(
+        XqfGame::read(&data).unwrap_err(),
+        XqfError::Truncated(_)
+    ));
+}
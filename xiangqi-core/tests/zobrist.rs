@@ -0,0 +1,75 @@
+use std::collections::HashSet;
+use xiangqi_core::*;
+
+fn mv(from: (usize, usize), to: (usize, usize)) -> Move {
+    Move::new(Position::new(from.0, from.1), Position::new(to.0, to.1))
+}
+
+fn recomputed(board: &Board) -> u64 {
+    Board::from_fen(&board.to_fen()).unwrap().zobrist()
+}
+
+#[test]
+fn incremental_hash_matches_a_fresh_one() {
+    let mut board = Board::default();
+    // Walk a deterministic game, always taking the first capture if there is one
+    for ply in 0..80 {
+        let moves = board.legal_moves();
+        if moves.is_empty() {
+            break;
+        }
+        let mv = moves
+            .iter()
+            .copied()
+            .find(|mv| !board.get(mv.to).is_empty())
+            .unwrap_or(moves[ply * 7 % moves.len()]);
+        board.make_move(mv);
+        assert_eq!(board.zobrist(), recomputed(&board), "at ply {}", ply);
+    }
+}
+
+#[test]
+fn unmake_restores_the_hash() {
+    let mut board = Board::default();
+    let before = board.zobrist();
+    for legal in board.legal_moves() {
+        let undo = board.make_move(legal);
+        assert_ne!(board.zobrist(), before);
+        board.unmake_move(undo);
+        assert_eq!(board.zobrist(), before);
+    }
+}
+
+#[test]
+fn side_to_move_changes_the_hash() {
+    let red = Board::from_fen("4k4/9/9/9/9/9/9/9/9/3K5 w").unwrap();
+    let black = Board::from_fen("4k4/9/9/9/9/9/9/9/9/3K5 b").unwrap();
+    assert_ne!(red.zobrist(), black.zobrist());
+    assert_ne!(red, black);
+}
+
+#[test]
+fn transpositions_are_equal() {
+    let mut first = Board::default();
+    first.make_move(mv((2, 7), (2, 4)));
+    first.make_move(mv((9, 7), (7, 6)));
+    first.make_move(mv((0, 7), (2, 6)));
+
+    let mut second = Board::default();
+    second.make_move(mv((0, 7), (2, 6)));
+    second.make_move(mv((9, 7), (7, 6)));
+    second.make_move(mv((2, 7), (2, 4)));
+
+    assert_eq!(first.zobrist(), second.zobrist());
+    assert_eq!(first, second);
+
+    let positions: HashSet<Board> = [first, second].into_iter().collect();
+    assert_eq!(positions.len(), 1);
+}
+
+#[test]
+fn move_counters_do_not_affect_equality() {
+    let board = Board::from_fen("4k4/9/9/9/9/9/9/9/9/3K5 w - - 0 1").unwrap();
+    let later = Board::from_fen("4k4/9/9/9/9/9/9/9/9/3K5 w - - 30 40").unwrap();
+    assert_eq!(board, later);
+}
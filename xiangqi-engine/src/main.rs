@@ -0,0 +1,292 @@
+use std::io::BufRead;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use xiangqi_core::*;
+
+const NAME: &str = concat!("xiangqi-engine ", env!("CARGO_PKG_VERSION"));
+const DEFAULT_HASH: usize = 16;
+const MAX_HASH: usize = 4096;
+const MAX_THREADS: usize = 256;
+/// How many moves the clock has to last for when the GUI does not say.
+const MOVES_TO_GO: u32 = 30;
+/// Milliseconds left on the clock when the last move before the time control uses the rest.
+const SAFETY_MARGIN: u64 = 50;
+
+/// A search running on its own thread, which hands the searcher back when it is done.
+struct Search {
+    /// Set by `stop`, and also what an infinite search waits for.
+    stopped: Arc<AtomicBool>,
+    flag: Arc<AtomicBool>,
+    handle: JoinHandle<Searcher>,
+}
+
+struct Engine {
+    protocol: Protocol,
+    /// Whether UCCI times are in milliseconds rather than seconds.
+    millis: bool,
+    board: Board,
+    threads: usize,
+    /// Taken while a search runs.
+    searcher: Option<Searcher>,
+    search: Option<Search>,
+}
+
+impl Engine {
+    fn new() -> Self {
+        Self {
+            protocol: Protocol::Ucci,
+            millis: false,
+            board: Board::default(),
+            threads: 1,
+            searcher: Some(Searcher::new(DEFAULT_HASH)),
+            search: None,
+        }
+    }
+
+    /// Handles one command, returning `false` once it is time to quit.
+    fn command(&mut self, line: &str) -> bool {
+        let mut words = line.split_whitespace();
+        match words.next() {
+            Some("ucci") => self.handshake(Protocol::Ucci),
+            Some("uci") => self.handshake(Protocol::Uci),
+            Some("isready") => println!("readyok"),
+            Some("setoption") => self.set_option(words),
+            Some("ucinewgame") => self.searcher().clear(),
+            Some("position") => self.position(words),
+            Some("go") => self.go(words),
+            Some("stop") => self.stop(),
+            Some("quit") => {
+                self.wait();
+                if self.protocol == Protocol::Ucci {
+                    println!("bye");
+                }
+                return false;
+            }
+            Some(command) => println!("info string Unknown command {}", command),
+            None => {}
+        }
+        true
+    }
+
+    fn handshake(&mut self, protocol: Protocol) {
+        self.protocol = protocol;
+        match protocol {
+            Protocol::Ucci => {
+                println!("id name {}", NAME);
+                println!("option usemillisec type check default false");
+                println!(
+                    "option hashsize type spin min 1 max {} default {}",
+                    MAX_HASH, DEFAULT_HASH
+                );
+                println!(
+                    "option threads type spin min 1 max {} default 1",
+                    MAX_THREADS
+                );
+                println!("option weights type string default <empty>");
+            }
+            Protocol::Uci => {
+                println!("id name {}", NAME);
+                println!(
+                    "option name Hash type spin default {} min 1 max {}",
+                    DEFAULT_HASH, MAX_HASH
+                );
+                println!(
+                    "option name Threads type spin default 1 min 1 max {}",
+                    MAX_THREADS
+                );
+                println!("option name Weights type string default <empty>");
+            }
+        }
+        println!("{}", protocol.ok());
+    }
+
+    /// Reads both `setoption <name> <value>` and `setoption name <name> value <value>`.
+    fn set_option<'a>(&mut self, mut words: impl Iterator<Item = &'a str>) {
+        let (name, value) = match words.next() {
+            Some("name") => {
+                let words: Vec<&str> = words.collect();
+                let split = words.iter().position(|word| *word == "value");
+                let (name, value) = words.split_at(split.unwrap_or(words.len()));
+                (name.join(" "), value.iter().skip(1).copied().collect())
+            }
+            Some(name) => (name.to_string(), words.collect::<Vec<_>>().join(" ")),
+            None => return,
+        };
+        let number = |max| value.parse().ok().filter(|value| (1..=max).contains(value));
+        match name.to_lowercase().as_str() {
+            "usemillisec" => self.millis = value == "true",
+            "hash" | "hashsize" => match number(MAX_HASH) {
+                Some(hash) => {
+                    let weights = self.searcher().weights().clone();
+                    let mut searcher = Searcher::new(hash);
+                    searcher.set_weights(weights);
+                    searcher.set_threads(self.threads);
+                    self.searcher = Some(searcher);
+                }
+                None => println!("info string Bad hash size {}", value),
+            },
+            "threads" => match number(MAX_THREADS) {
+                Some(threads) => {
+                    self.threads = threads;
+                    self.searcher().set_threads(threads);
+                }
+                None => println!("info string Bad thread count {}", value),
+            },
+            // A weight file, as read by `Weights::load`
+            "weights" => match Weights::load(&value) {
+                Ok(weights) => self.searcher().set_weights(weights),
+                Err(err) => println!("info string Bad weights {}: {}", value, err),
+            },
+            _ => println!("info string Unknown option {}", name),
+        }
+    }
+
+    /// Reads `position {fen <fen> | startpos} [moves <move>...]`.
+    fn position<'a>(&mut self, mut words: impl Iterator<Item = &'a str>) {
+        let mut board = match words.next() {
+            Some("startpos") => Board::default(),
+            Some("fen") => {
+                let fen: Vec<&str> = words.by_ref().take_while(|word| *word != "moves").collect();
+                match Board::from_fen(&fen.join(" ")) {
+                    Ok(board) => board,
+                    Err(err) => return println!("info string Bad position: {}", err),
+                }
+            }
+            _ => return println!("info string Bad position command"),
+        };
+        for word in words.skip_while(|word| *word == "moves") {
+            let checked = Move::from_iccs(word).and_then(|mv| board.check_move(mv).map(|_| mv));
+            match checked {
+                Ok(mv) => {
+                    board.make_move(mv);
+                }
+                Err(err) => return println!("info string Bad move {}: {}", word, err),
+            }
+        }
+        self.board = board;
+    }
+
+    fn limits<'a>(&self, mut words: impl Iterator<Item = &'a str>) -> (SearchLimits, bool) {
+        let mut limits = SearchLimits::default();
+        let mut infinite = false;
+        let (mut clock, mut increment, mut moves_to_go) = (None, 0, MOVES_TO_GO);
+        // UCCI times are in seconds unless the GUI asked for milliseconds
+        let unit = match self.protocol {
+            Protocol::Ucci if !self.millis => 1000,
+            _ => 1,
+        };
+        let (own_time, own_increment) = match self.board.turn() {
+            PieceColor::Red => ("wtime", "winc"),
+            PieceColor::Black => ("btime", "binc"),
+        };
+        while let Some(word) = words.next() {
+            let mut number = || words.next().and_then(|word| word.parse::<u64>().ok());
+            match word {
+                "depth" => limits.depth = number().map_or(MAX_DEPTH, |depth| depth as u32),
+                "nodes" => limits.nodes = number(),
+                "movetime" => limits.time = number().map(Duration::from_millis),
+                "time" => clock = number().map(|time| time * unit),
+                "increment" => increment = number().map_or(0, |time| time * unit),
+                word if word == own_time => clock = number(),
+                word if word == own_increment => increment = number().unwrap_or(0),
+                "movestogo" => moves_to_go = number().map_or(MOVES_TO_GO, |moves| moves as u32),
+                "infinite" => infinite = true,
+                _ => {}
+            }
+        }
+        if let Some(clock) = clock.filter(|_| limits.time.is_none()) {
+            // Half the clock at most, unless no more moves have to fit into it
+            let most = match moves_to_go {
+                0 | 1 => clock.saturating_sub(SAFETY_MARGIN),
+                _ => clock / 2,
+            };
+            let budget = (clock / moves_to_go.max(1) as u64 + increment)
+                .min(most)
+                .max(1);
+            println!("info string Time budget {} ms", budget);
+            limits.time = Some(Duration::from_millis(budget));
+        }
+        (limits, infinite)
+    }
+
+    fn go<'a>(&mut self, words: impl Iterator<Item = &'a str>) {
+        let (limits, infinite) = self.limits(words);
+        let mut searcher = self.searcher.take().unwrap_or_else(|| self.wait());
+        let stopped = Arc::new(AtomicBool::new(false));
+        let flag = searcher.stop_flag();
+        let board = self.board.clone();
+        let protocol = self.protocol;
+        let (search_stopped, search_flag) = (stopped.clone(), flag.clone());
+        let handle = std::thread::spawn(move || {
+            let mut pv = Vec::new();
+            let mv = searcher.search(&board, limits, |info| {
+                println!("{}", EngineInfo::from(info).to_line(protocol));
+                pv = info.pv.clone();
+                // The searcher clears its flag when it starts, which may have lost a quick stop
+                if search_stopped.load(Ordering::Relaxed) {
+                    search_flag.store(true, Ordering::Relaxed);
+                }
+            });
+            // An infinite search only answers once told to stop
+            while infinite && !search_stopped.load(Ordering::Relaxed) {
+                std::thread::park();
+            }
+            match (mv, protocol) {
+                (Some(mv), _) => match pv.get(1).filter(|_| pv.first() == Some(&mv)) {
+                    Some(ponder) => println!("bestmove {} ponder {}", mv, ponder),
+                    None => println!("bestmove {}", mv),
+                },
+                (None, Protocol::Ucci) => println!("nobestmove"),
+                (None, Protocol::Uci) => println!("bestmove (none)"),
+            }
+            searcher
+        });
+        self.search = Some(Search {
+            stopped,
+            flag,
+            handle,
+        });
+    }
+
+    fn stop(&mut self) {
+        if let Some(ref search) = self.search {
+            search.stopped.store(true, Ordering::Relaxed);
+            search.flag.store(true, Ordering::Relaxed);
+            search.handle.thread().unpark();
+        }
+    }
+
+    /// Stops the running search, and takes back its searcher.
+    fn wait(&mut self) -> Searcher {
+        self.stop();
+        match self.search.take() {
+            Some(search) => search.handle.join().expect("search thread panicked"),
+            None => self.searcher.take().expect("searcher is idle"),
+        }
+    }
+
+    /// The idle searcher, stopping the running search if there is one.
+    fn searcher(&mut self) -> &mut Searcher {
+        if self.searcher.is_none() {
+            let searcher = self.wait();
+            self.searcher = Some(searcher);
+        }
+        self.searcher.as_mut().unwrap()
+    }
+}
+
+fn main() {
+    let mut engine = Engine::new();
+    for line in std::io::stdin().lock().lines() {
+        let Ok(line) = line else {
+            break;
+        };
+        if !engine.command(&line) {
+            return;
+        }
+    }
+    // The GUI went away without saying goodbye
+    engine.wait();
+}
@@ -0,0 +1,148 @@
+use std::time::{Duration, Instant};
+use xiangqi_core::*;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+fn launch(protocol: Protocol) -> ExternalEngine {
+    ExternalEngine::launch(env!("CARGO_BIN_EXE_xiangqi-engine"), protocol).unwrap()
+}
+
+#[test]
+fn answers_ucci() {
+    let mut engine = launch(Protocol::Ucci);
+    engine.set_mate_score(MATE);
+    assert!(engine.name().unwrap().starts_with("xiangqi-engine"));
+    assert!(engine
+        .options
+        .iter()
+        .any(|option| option.starts_with("hashsize")));
+    engine.set_option("hashsize", "4").unwrap();
+    engine.set_option("threads", "2").unwrap();
+    engine.wait_ready().unwrap();
+
+    let board = Board::from_fen("4k4/R8/1R7/9/9/9/9/9/9/3K5 w").unwrap();
+    engine.position(&board, &[]).unwrap();
+    engine.go(&SearchLimits::depth(3)).unwrap();
+    let mut infos = Vec::new();
+    let mv = engine
+        .best_move(TIMEOUT, |info| infos.push(info.clone()))
+        .unwrap();
+    assert_eq!(mv, Some("b7b9".parse().unwrap()));
+    assert_eq!(infos.last().unwrap().score, Some(Score::Mate(1)));
+    engine.quit();
+}
+
+#[test]
+fn follows_moves_and_limits() {
+    let mut engine = launch(Protocol::Uci);
+    let mut history = GameHistory::new(Board::default());
+    history.play("h2e2".parse().unwrap()).unwrap();
+    engine.history(&history).unwrap();
+    engine
+        .go(&SearchLimits {
+            nodes: Some(2_000),
+            ..Default::default()
+        })
+        .unwrap();
+    let mut nodes = 0;
+    let mv = engine
+        .best_move(TIMEOUT, |info| nodes = info.nodes.unwrap())
+        .unwrap()
+        .unwrap();
+    assert_eq!(
+        history.board().get(mv.from).color(),
+        Some(PieceColor::Black)
+    );
+    assert!(nodes <= 2_000);
+
+    let start = Instant::now();
+    engine
+        .go(&SearchLimits::time(Duration::from_millis(200)))
+        .unwrap();
+    assert!(engine.best_move(TIMEOUT, |_| {}).unwrap().is_some());
+    assert!(start.elapsed() < Duration::from_secs(5));
+    engine.quit();
+}
+
+#[test]
+fn budgets_the_clock() {
+    let mut engine = launch(Protocol::Uci);
+    engine.position(&Board::default(), &[]).unwrap();
+    let mut budget = |go: &str| {
+        engine.send(go).unwrap();
+        let mut budget = None;
+        engine
+            .best_move(TIMEOUT, |info| {
+                if let Some(ref text) = info.string {
+                    budget = text.strip_prefix("Time budget ").map(str::to_string);
+                }
+            })
+            .unwrap();
+        budget.unwrap()
+    };
+    assert_eq!(budget("go depth 1 wtime 2000 movestogo 2"), "1000 ms");
+    // The last move before the time control may use all but a safety margin
+    assert_eq!(budget("go depth 1 wtime 2000 movestogo 1"), "1950 ms");
+    assert_eq!(
+        budget("go depth 1 wtime 2000 winc 100 movestogo 1"),
+        "1950 ms"
+    );
+    engine.quit();
+}
+
+#[test]
+fn infinite_search_waits_for_stop() {
+    let mut engine = launch(Protocol::Uci);
+    // Mate is found at once, but the answer still has to wait
+    let board = Board::from_fen("4k4/R8/1R7/9/9/9/9/9/9/3K5 w").unwrap();
+    engine.position(&board, &[]).unwrap();
+    engine.go(&SearchLimits::default()).unwrap();
+    let waited = engine.best_move(Duration::from_millis(300), |_| {});
+    assert_eq!(waited, Err(EngineError::Timeout));
+    engine.stop().unwrap();
+    assert_eq!(
+        engine.best_move(TIMEOUT, |_| {}),
+        Ok(Some("b7b9".parse().unwrap()))
+    );
+    engine.quit();
+}
+
+#[test]
+fn reports_having_no_move() {
+    let mut engine = launch(Protocol::Ucci);
+    let board = Board::from_fen("1R2k4/R8/9/9/9/9/9/9/9/3K5 b").unwrap();
+    engine.position(&board, &[]).unwrap();
+    engine.go(&SearchLimits::depth(2)).unwrap();
+    assert_eq!(engine.best_move(TIMEOUT, |_| {}), Ok(None));
+    engine.quit();
+}
+
+#[test]
+fn loads_weights() {
+    let board = Board::from_fen("4k4/9/9/9/9/9/9/9/9/R2K5 w").unwrap();
+    let score = |engine: &mut ExternalEngine| {
+        engine.position(&board, &[]).unwrap();
+        engine.go(&SearchLimits::depth(1)).unwrap();
+        let mut score = None;
+        engine
+            .best_move(TIMEOUT, |info| score = info.score)
+            .unwrap();
+        match score {
+            Some(Score::Cp(score)) => score,
+            score => panic!("unexpected score {:?}", score),
+        }
+    };
+    let mut engine = launch(Protocol::Uci);
+    let before = score(&mut engine);
+
+    let path = std::env::temp_dir().join(format!("weights-{}.txt", std::process::id()));
+    std::fs::write(&path, "material.rook = 3000\n").unwrap();
+    engine
+        .set_option("Weights", path.to_str().unwrap())
+        .unwrap();
+    engine.wait_ready().unwrap();
+    let after = score(&mut engine);
+    std::fs::remove_file(&path).unwrap();
+    assert!(after > before + 1000, "{} is not above {}", after, before);
+    engine.quit();
+}
@@ -1,11 +1,13 @@
 mod camera;
 mod marker;
+mod opening;
 mod pieces;
 mod win_lose;
 
 pub(super) use crate::prelude::*;
 pub use camera::*;
 pub use marker::*;
+pub use opening::*;
 pub use pieces::*;
 pub use win_lose::*;
 
@@ -21,15 +23,16 @@ impl Plugin for ComponentsPlugin {
         app.add_systems(Startup, (spawn_camera,));
         app.add_systems(
             OnEnter(Status::Play),
-            (start_game, spawn_pieces, spawn_marker).chain(),
+            (start_game, spawn_pieces, spawn_marker, spawn_opening).chain(),
         );
         app.add_systems(
             OnExit(Status::Play),
-            (end_game, despawn_marker, despawn_win_lose),
+            (end_game, despawn_marker, despawn_win_lose, despawn_opening),
         );
         app.add_systems(
             Update,
-            (update_pieces, move_marker, listen_win_lose).run_if(in_state(Status::Play)),
+            (update_pieces, move_marker, listen_win_lose, update_opening)
+                .run_if(in_state(Status::Play)),
         );
     }
 }
@@ -0,0 +1,83 @@
+use super::*;
+use bevy::sprite::Anchor;
+
+/// How many book moves the panel lists.
+const SHOWN_MOVES: usize = 5;
+
+#[derive(Component)]
+pub struct OpeningMarker;
+
+/// The latest named opening the game has gone through.
+#[derive(Debug, Default, Resource)]
+pub struct OpeningName(pub Option<String>);
+
+fn describe(board: &Board, book: &OpeningBook, name: &mut OpeningName) -> String {
+    if let Some(found) = book.name(board) {
+        name.0 = Some(found.to_string());
+    }
+    let mut lines = vec![format!(
+        "Opening: {}",
+        name.0.as_deref().unwrap_or("Unknown")
+    )];
+    let moves = book.moves(board);
+    let total: u32 = moves.iter().map(|book| book.weight).sum();
+    if !moves.is_empty() {
+        lines.push("Book:".to_string());
+    }
+    for book in moves.iter().take(SHOWN_MOVES) {
+        lines.push(format!(
+            "{} {}%",
+            board.to_chinese(book.mv),
+            book.weight * 100 / total
+        ));
+    }
+    lines.join("\n")
+}
+
+pub(super) fn spawn_opening(
+    mut commands: Commands,
+    board: Res<BoardInfo>,
+    book: Res<Book>,
+    font: Res<DefaultFont>,
+) {
+    let mut name = OpeningName::default();
+    let text = describe(&board.board, &book.0, &mut name);
+    commands.insert_resource(name);
+    commands.spawn((
+        OpeningMarker,
+        Text2dBundle {
+            text: Text::from_section(
+                text,
+                TextStyle {
+                    font: font.0.clone(),
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                },
+            ),
+            text_anchor: Anchor::TopLeft,
+            transform: Transform::from_xyz(-WIDTH / 2.0 + 20.0, HEIGHT / 2.0 - 20.0, 3.0),
+            ..Default::default()
+        },
+    ));
+}
+
+pub(super) fn update_opening(
+    mut event: EventReader<UpdateEvent>,
+    board: Res<BoardInfo>,
+    book: Res<Book>,
+    mut name: ResMut<OpeningName>,
+    mut text: Query<&mut Text, With<OpeningMarker>>,
+) {
+    if event.read().count() == 0 {
+        return;
+    }
+    let value = describe(&board.board, &book.0, &mut name);
+    text.iter_mut()
+        .for_each(|mut text| text.sections[0].value = value.clone());
+}
+
+pub(super) fn despawn_opening(mut commands: Commands, opening: Query<Entity, With<OpeningMarker>>) {
+    opening.iter().for_each(|entity| {
+        commands.entity(entity).despawn_recursive();
+    })
+}
@@ -7,16 +7,17 @@ pub struct PieceMarker;
 pub struct TileMarker;
 
 pub fn locate_piece(pos: Position, color: PieceColor) -> Vec3 {
-    let result = Vec3::new(
+    // Black sees the board upside down
+    let pos = if color == PieceColor::Black {
+        pos.flipped()
+    } else {
+        pos
+    };
+    Vec3::new(
         (pos.file() as f32 - FILES as f32 / 2.0 + 0.5) * (*PIECE_EACH),
         (pos.rank() as f32 - RANKS as f32 / 2.0 + 0.5) * (*PIECE_EACH),
         0.0,
-    );
-    if color == PieceColor::Black {
-        -result
-    } else {
-        result
-    }
+    )
 }
 
 pub fn locate_position(pos: Vec2, color: PieceColor) -> Position {
@@ -32,7 +33,9 @@ pub fn locate_position(pos: Vec2, color: PieceColor) -> Position {
 }
 
 pub(super) fn start_game(mut info: ResMut<BoardInfo>, mut update: EventWriter<UpdateEvent>) {
-    info.board = Board::default();
+    info.reset(Board::default());
+    info.ply = 0;
+    info.outcome = None;
     update.send(UpdateEvent);
     info!("Game started");
 }
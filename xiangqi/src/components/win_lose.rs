@@ -15,7 +15,6 @@ impl Default for GameJustStarted {
 pub(super) fn listen_win_lose(
     mut commands: Commands,
     mut event: EventReader<UpdateEvent>,
-    moves: Res<Moves>,
     board: Res<BoardInfo>,
     font: Res<DefaultFont>,
     connect: Res<Connection>,
@@ -24,17 +23,20 @@ pub(super) fn listen_win_lose(
 ) {
     if let Some(ref player) = connect.player {
         event.read().for_each(|_| {
-            if !started.0 && win_lose.iter().next().is_none() && moves.moves.is_empty() {
-                let (text, color) = if board.board.turn() == player.color {
-                    ("You Lose!", Color::RED)
-                } else {
-                    ("You Win!", Color::GREEN)
+            if started.0 || win_lose.iter().next().is_some() {
+                return;
+            }
+            if let Some(outcome) = board.outcome {
+                let color = match outcome.winner() {
+                    Some(winner) if winner == player.color => Color::GREEN,
+                    Some(_) => Color::RED,
+                    None => Color::YELLOW,
                 };
                 commands.spawn((
                     WinLoseMarker,
                     Text2dBundle {
                         text: Text::from_section(
-                            text,
+                            outcome.to_string(),
                             TextStyle {
                                 font: font.0.clone(),
                                 font_size: 70.0,
@@ -3,4 +3,3 @@ pub mod menu;
 pub mod prelude;
 pub mod resources;
 pub mod status;
-pub mod types;
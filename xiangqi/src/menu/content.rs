@@ -1,12 +1,18 @@
 use super::*;
 
 #[derive(Debug, Event)]
-pub struct LaunchEvent;
+pub enum LaunchEvent {
+    Connect,
+    /// Plays Red against the built-in computer.
+    Computer,
+}
 
 #[derive(Debug, Resource)]
 pub struct MenuContents {
     pub url: String,
     pub room: String,
+    pub level: Level,
+    pub style: PlayStyle,
 }
 
 impl Default for MenuContents {
@@ -14,6 +20,8 @@ impl Default for MenuContents {
         Self {
             url: "http://127.0.0.1:8082".to_string(),
             room: "example-room-code".to_string(),
+            level: Level::default(),
+            style: PlayStyle::default(),
         }
     }
 }
@@ -39,7 +47,24 @@ pub(super) fn menu_ui(
         ui.label("Room Code:");
         ui.text_edit_singleline(&mut contents.room);
         if ui.button("Connect").clicked() {
-            launch.send(LaunchEvent);
+            launch.send(LaunchEvent::Connect);
+        }
+        egui::ComboBox::from_label("Level")
+            .selected_text(contents.level.name())
+            .show_ui(ui, |ui| {
+                for level in Level::ALL {
+                    ui.selectable_value(&mut contents.level, level, level.name());
+                }
+            });
+        egui::ComboBox::from_label("Style")
+            .selected_text(contents.style.name())
+            .show_ui(ui, |ui| {
+                for style in PlayStyle::ALL {
+                    ui.selectable_value(&mut contents.style, style, style.name());
+                }
+            });
+        if ui.button("Play Computer").clicked() {
+            launch.send(LaunchEvent::Computer);
         }
     });
 }
@@ -47,10 +72,21 @@ pub(super) fn menu_ui(
 pub(super) fn launch_game(
     mut launch: EventReader<LaunchEvent>,
     mut connect: ResMut<Connection>,
+    mut computer: ResMut<Computer>,
     contents: Res<MenuContents>,
     mut event: EventWriter<ConnectEvent>,
 ) {
-    launch.read().for_each(|_| {
+    launch.read().for_each(|launch| {
+        if let LaunchEvent::Computer = launch {
+            computer.level = contents.level;
+            computer.style = contents.style;
+            connect.opponent = Opponent::Computer;
+            connect.player = Some(Player {
+                color: PieceColor::Red,
+            });
+            return;
+        }
+        connect.opponent = Opponent::Remote;
         connect.url = contents.url.clone();
         let mut value = 0u64;
         let mut base = 1u64;
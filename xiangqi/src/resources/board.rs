@@ -3,6 +3,11 @@ use super::*;
 #[derive(Debug, Default, Resource)]
 pub struct BoardInfo {
     pub board: Board,
+    pub ply: Ply,
+    /// Set by the server once the game is over, or by the client in games without one.
+    pub outcome: Option<GameOutcome>,
+    /// The moves since the board was last set, which the repetition rules look at.
+    pub history: GameHistory,
 }
 
 impl BoardInfo {
@@ -12,7 +17,37 @@ impl BoardInfo {
 
     pub fn de(&mut self, s: &str) {
         if let Ok(board) = s.try_into() {
-            self.board = board;
+            self.reset(board);
+        }
+    }
+
+    /// Starts over from `board`, forgetting how it was reached.
+    pub fn reset(&mut self, board: Board) {
+        self.history = GameHistory::new(board.clone());
+        self.board = board;
+    }
+
+    /// Plays a move already known to be legal.
+    pub fn play(&mut self, mv: Move) {
+        self.board.make_move(mv);
+        if let Err(err) = self.history.play(mv) {
+            warn!("Unable to record {} in the history: {}", mv, err);
+        }
+        self.ply += 1;
+    }
+
+    /// How the game ends by the moves played here, for games without a server to judge them.
+    pub fn judge(&mut self) {
+        self.outcome = self.history.outcome(&GameRules::default());
+    }
+
+    pub fn resync(&mut self, resync: &Resync) {
+        if let Ok(board) = resync.board.as_str().try_into() {
+            self.reset(board);
+            self.ply = resync.ply;
+            self.outcome = resync.outcome;
+        } else {
+            warn!("Unable to resync to {:?}", resync);
         }
     }
 }
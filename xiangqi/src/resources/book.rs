@@ -0,0 +1,26 @@
+use super::*;
+use std::sync::Arc;
+
+/// Where a book built with the `book` tool is looked for.
+pub static BOOK_PATH: &str = "assets/book.bin";
+
+/// The opening book, shared with the computer while it thinks.
+#[derive(Resource)]
+pub struct Book(pub Arc<OpeningBook>);
+
+pub(super) fn init_book(mut commands: Commands) {
+    let book = match OpeningBook::load(BOOK_PATH) {
+        Ok(book) => {
+            info!("Loaded {} book positions from {}", book.len(), BOOK_PATH);
+            book
+        }
+        Err(err) => {
+            info!(
+                "Using the standard openings, as {} is unusable: {}",
+                BOOK_PATH, err
+            );
+            OpeningBook::standard()
+        }
+    };
+    commands.insert_resource(Book(Arc::new(book)));
+}
@@ -0,0 +1,91 @@
+use super::*;
+use bevy::tasks::{block_on, poll_once, AsyncComputeTaskPool, Task};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// The built-in engine, which searches on the async compute pool so that frames keep coming.
+#[derive(Default, Resource)]
+pub struct Computer {
+    pub level: Level,
+    pub style: PlayStyle,
+    searcher: Option<Searcher>,
+    thinking: Option<Task<(Searcher, Option<Move>)>>,
+    stop: Option<Arc<AtomicBool>>,
+}
+
+pub(super) fn init_computer(mut commands: Commands) {
+    commands.init_resource::<Computer>();
+}
+
+pub(super) fn start_thinking(
+    mut computer: ResMut<Computer>,
+    board: Res<BoardInfo>,
+    book: Res<Book>,
+    connect: Res<Connection>,
+) {
+    let Some(ref player) = connect.player else {
+        return;
+    };
+    if connect.is_remote()
+        || computer.thinking.is_some()
+        || board.outcome.is_some()
+        || board.board.turn() == player.color
+    {
+        return;
+    }
+
+    let mut searcher = computer.searcher.take().unwrap_or_default();
+    let weights = computer.style.weights();
+    if *searcher.weights() != weights {
+        searcher.set_weights(weights);
+    }
+    computer.stop = Some(searcher.stop_flag());
+    let level = computer.level;
+    let book = book.0.clone();
+    let position = board.board.clone();
+    computer.thinking = Some(AsyncComputeTaskPool::get().spawn(async move {
+        // Book moves vary the opening, and leave the search for when the book runs out
+        let mv = book.pick(&position).or_else(|| {
+            level.choose(&mut searcher, &position, |info| {
+                info!(
+                    "Computer at depth {}: score {}, {} nodes",
+                    info.depth, info.score, info.nodes
+                )
+            })
+        });
+        (searcher, mv)
+    }));
+    info!("{} computer is thinking", level);
+}
+
+pub(super) fn finish_thinking(
+    mut computer: ResMut<Computer>,
+    mut board: ResMut<BoardInfo>,
+    mut update: EventWriter<UpdateEvent>,
+) {
+    let Some(result) = computer
+        .thinking
+        .as_mut()
+        .and_then(|task| block_on(poll_once(task)))
+    else {
+        return;
+    };
+    computer.thinking = None;
+    let (searcher, mv) = result;
+    computer.searcher = Some(searcher);
+
+    if let Some(mv) = mv {
+        info!("Computer plays {}", mv);
+        board.play(mv);
+    }
+    board.judge();
+    update.send(UpdateEvent);
+}
+
+pub(super) fn stop_thinking(mut computer: ResMut<Computer>) {
+    if let Some(stop) = computer.stop.take() {
+        stop.store(true, Ordering::Relaxed);
+    }
+    // The search returns soon after being stopped, and its result is of no use any more
+    computer.thinking = None;
+}
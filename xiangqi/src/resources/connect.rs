@@ -5,11 +5,25 @@ pub struct Player {
     pub color: PieceColor,
 }
 
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Opponent {
+    /// Another player, through the server.
+    #[default]
+    Remote,
+    /// The built-in engine, without any server.
+    Computer,
+}
+
 #[derive(Debug, Clone, Default, Resource)]
 pub struct Connection {
     pub url: String,
     pub room: RoomId,
+    /// Sent with every request, so the server knows which seat it comes from.
+    ///
+    /// Kept after the game ends, which still has to be left with it.
+    pub token: Token,
     pub player: Option<Player>,
+    pub opponent: Opponent,
 }
 
 #[derive(Debug, Event)]
@@ -19,6 +33,15 @@ impl Connection {
     pub fn is_connected(&self) -> bool {
         self.player.is_some()
     }
+
+    pub fn is_remote(&self) -> bool {
+        self.opponent == Opponent::Remote
+    }
+}
+
+/// Run condition for the systems that talk to the server.
+pub fn remote_game(connect: Res<Connection>) -> bool {
+    connect.is_remote()
 }
 
 pub(super) fn listen_connect_event(
@@ -53,7 +76,8 @@ pub(super) fn update_connection_player(
             );
             connect.player = Some(Player {
                 color: response.player.into(),
-            })
+            });
+            connect.token = response.token;
         } else {
             warn!("Connection failed");
         }
@@ -71,7 +95,13 @@ pub(super) fn test_disconnect(
 ) {
     if connect.player.is_none() {
         status.set(Status::Menu);
-        let body = DisconnectRequest { room: connect.room };
+        if !connect.is_remote() {
+            return;
+        }
+        let body = DisconnectRequest {
+            room: connect.room,
+            token: connect.token,
+        };
         request.send(
             HttpClient::new()
                 .json(&body)
@@ -41,6 +41,56 @@ pub(super) fn listen_click(
     }
 }
 
+/// R resigns, D offers a draw or accepts the opponent's offer.
+///
+/// The computer never agrees to a draw.
+pub(super) fn listen_game_keys(
+    key: Res<ButtonInput<KeyCode>>,
+    mut request: EventWriter<HttpRequest>,
+    mut update: EventWriter<UpdateEvent>,
+    mut board: ResMut<BoardInfo>,
+    connect: Res<Connection>,
+) {
+    if let (Some(player), None) = (&connect.player, board.outcome) {
+        if !connect.is_remote() {
+            if key.just_pressed(KeyCode::KeyR) {
+                info!("Resigning");
+                board.outcome = Some(GameOutcome::wins(
+                    player.color.opposite(),
+                    WinReason::Resignation,
+                ));
+                update.send(UpdateEvent);
+            }
+            return;
+        }
+        if key.just_pressed(KeyCode::KeyR) {
+            info!("Resigning");
+            let body = ResignRequest {
+                room: connect.room,
+                token: connect.token,
+            };
+            request.send(
+                HttpClient::new()
+                    .json(&body)
+                    .post(format!("{}/resign", connect.url))
+                    .build(),
+            );
+        } else if key.just_pressed(KeyCode::KeyD) {
+            info!("Offering a draw");
+            let body = DrawRequest {
+                room: connect.room,
+                token: connect.token,
+            };
+            request.send(
+                HttpClient::new()
+                    .json(&body)
+                    .post(format!("{}/draw", connect.url))
+                    .build(),
+            );
+        }
+    }
+}
+
 pub(super) fn listen_end_game(key: Res<ButtonInput<KeyCode>>, mut connect: ResMut<Connection>) {
     if key.just_pressed(KeyCode::Escape) {
         connect.player = None;
@@ -1,18 +1,24 @@
 mod board;
+mod book;
+mod computer;
 mod connect;
 mod control;
 mod fonts;
 mod images;
 mod moves;
+mod push;
 mod query;
 
 pub(super) use crate::prelude::*;
 pub use board::*;
+pub use book::*;
+pub use computer::*;
 pub use connect::*;
 pub use control::*;
 pub use fonts::*;
 pub use images::*;
 pub use moves::*;
+pub use push::*;
 pub use query::*;
 
 pub struct ResourcesPlugin;
@@ -22,20 +28,27 @@ impl Plugin for ResourcesPlugin {
         app.add_event::<UpdateEvent>()
             .add_event::<TryMoveEvent>()
             .add_event::<DoMoveEvent>()
+            .add_event::<SyncEvent>()
+            .add_event::<RoomUpdateEvent>()
             .add_event::<ConnectEvent>();
         app.register_request_type::<ConnectResponse>()
-            .register_request_type::<QueryResponse>();
+            .register_request_type::<QueryResponse>()
+            .register_request_type::<Resync>();
         app.add_systems(
             Startup,
             (
                 init_images,
                 init_board,
+                init_book,
                 init_connection,
                 init_moves,
                 init_control,
                 init_fonts,
+                init_computer,
             ),
         );
+        app.add_systems(OnEnter(Status::Play), open_push.run_if(remote_game));
+        app.add_systems(OnExit(Status::Play), (close_push, stop_thinking));
         app.add_systems(
             Update,
             (
@@ -44,12 +57,25 @@ impl Plugin for ResourcesPlugin {
                 verify_move,
                 do_move,
                 listen_click,
-                query_moves,
-                respond_moves,
+                listen_game_keys,
                 listen_end_game,
+                start_thinking,
+                finish_thinking,
             )
                 .run_if(in_state(Status::Play)),
         );
+        app.add_systems(
+            Update,
+            (
+                query_moves,
+                respond_moves,
+                receive_push,
+                apply_room_update,
+                listen_sync,
+                respond_sync,
+            )
+                .run_if(in_state(Status::Play).and_then(remote_game)),
+        );
         app.add_systems(Update, (listen_connect_event, update_connection_player));
     }
 }
@@ -2,7 +2,7 @@ use super::*;
 
 #[derive(Debug, Default, Resource)]
 pub struct Moves {
-    pub moves: HashMap<Position, HashSet<Position>>,
+    pub moves: Vec<Move>,
 }
 
 #[derive(Debug, Clone, Event)]
@@ -30,28 +30,7 @@ pub(super) fn listen_update(
     mut moves: ResMut<Moves>,
 ) {
     update.read().for_each(|_| {
-        let map = Arc::new(RwLock::new(HashMap::<Position, HashSet<Position>>::new()));
-        let pool = threadpool::Builder::new().build();
-        for rank in 0..RANKS {
-            for file in 0..FILES {
-                let pos = Position::new(rank, file);
-                if board.board.get(pos).is_color(board.board.turn()) {
-                    let reachable = board.board.reachable(pos);
-                    for to in reachable.into_iter() {
-                        let mut board = board.board.clone();
-                        let map = map.clone();
-                        pool.execute(move || {
-                            board.force(pos, to);
-                            if !board.is_check() {
-                                map.write().unwrap().entry(pos).or_default().insert(to);
-                            }
-                        });
-                    }
-                }
-            }
-        }
-        pool.join();
-        moves.moves = RwLock::into_inner(Arc::into_inner(map).unwrap()).unwrap();
+        moves.moves = board.board.legal_moves();
         info!("Updated available moves")
     });
 }
@@ -69,6 +48,10 @@ pub(super) fn verify_move(
             warn!("Connection is not available any more");
             return;
         }
+        if board.outcome.is_some() {
+            warn!("The game is already over");
+            return;
+        }
         if board.board.turn() != connect.player.as_ref().unwrap().color {
             warn!("It's not your turn");
             return;
@@ -77,12 +60,7 @@ pub(super) fn verify_move(
             warn!("You clicked on somewhere outside the board");
             return;
         }
-        if !moves
-            .moves
-            .get(&mv.from)
-            .map(|set| set.contains(&mv.to))
-            .unwrap_or_default()
-        {
+        if !moves.moves.contains(&Move::new(mv.from, mv.to)) {
             warn!("This move is illegal according to the rules");
             return;
         }
@@ -103,16 +81,23 @@ pub(super) fn do_move(
     connect: Res<Connection>,
 ) {
     do_move.read().for_each(|mv| {
-        board.board.force(mv.from, mv.to);
-        board.board.next_turn();
-        update.send(UpdateEvent);
-
-        info!("Sending play request");
         let body = PlayRequest {
             room: connect.room,
-            player: connect.player.as_ref().unwrap().color.into(),
-            board: (&board.board).into(),
+            token: connect.token,
+            mv: transfer::Move {
+                ply: board.ply,
+                iccs: Move::new(mv.from, mv.to).to_iccs(),
+            },
         };
+        board.play(Move::new(mv.from, mv.to));
+        update.send(UpdateEvent);
+
+        if !connect.is_remote() {
+            // Nobody else keeps score against the computer
+            board.judge();
+            return;
+        }
+        info!("Sending play request");
         request.send(
             HttpClient::new()
                 .json(&body)
@@ -0,0 +1,111 @@
+use super::*;
+use std::ops::ControlFlow;
+use std::sync::mpsc::{channel, Receiver};
+use std::sync::Mutex;
+
+#[derive(Debug)]
+enum PushMessage {
+    Opened,
+    Update(RoomUpdate),
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PushStatus {
+    Connecting,
+    Open,
+    Failed,
+}
+
+#[derive(Resource)]
+pub struct PushChannel {
+    pub status: PushStatus,
+    receiver: Mutex<Receiver<PushMessage>>,
+}
+
+fn parse_events(buffer: &mut Vec<u8>) -> Vec<RoomUpdate> {
+    let mut result = Vec::new();
+    // Server-sent events are separated by a blank line
+    while let Some(end) = buffer.windows(2).position(|window| window == b"\n\n") {
+        let event: Vec<u8> = buffer.drain(..end + 2).collect();
+        for line in String::from_utf8_lossy(&event).lines() {
+            if let Some(data) = line.strip_prefix("data:") {
+                match serde_json::from_str(data.trim_start()) {
+                    Ok(update) => result.push(update),
+                    Err(err) => warn!("Unable to parse pushed update {:?}: {}", data, err),
+                }
+            }
+        }
+    }
+    result
+}
+
+pub(super) fn open_push(mut commands: Commands, connect: Res<Connection>) {
+    if connect.player.is_some() {
+        let url = format!("{}/events/{}/{}", connect.url, connect.room, connect.token);
+        info!("Opening push channel {:?}", url);
+
+        let (sender, receiver) = channel();
+        let buffer = Mutex::new(Vec::new());
+        ehttp::streaming::fetch(ehttp::Request::get(url), move |part| {
+            let messages = match part {
+                Ok(ehttp::streaming::Part::Response(response)) if response.ok => {
+                    vec![PushMessage::Opened]
+                }
+                Ok(ehttp::streaming::Part::Chunk(chunk)) if !chunk.is_empty() => {
+                    let mut buffer = buffer.lock().unwrap();
+                    buffer.extend_from_slice(&chunk);
+                    parse_events(&mut buffer)
+                        .into_iter()
+                        .map(PushMessage::Update)
+                        .collect()
+                }
+                // A failed response, an error or the end of the stream
+                _ => {
+                    let _ = sender.send(PushMessage::Failed);
+                    return ControlFlow::Break(());
+                }
+            };
+            for message in messages {
+                // The receiver is gone once the game is over
+                if sender.send(message).is_err() {
+                    return ControlFlow::Break(());
+                }
+            }
+            ControlFlow::Continue(())
+        });
+
+        commands.insert_resource(PushChannel {
+            status: PushStatus::Connecting,
+            receiver: Mutex::new(receiver),
+        });
+    }
+}
+
+pub(super) fn close_push(mut commands: Commands) {
+    commands.remove_resource::<PushChannel>();
+}
+
+pub(super) fn receive_push(
+    push: Option<ResMut<PushChannel>>,
+    mut room_update: EventWriter<RoomUpdateEvent>,
+) {
+    if let Some(mut push) = push {
+        let messages: Vec<PushMessage> = push.receiver.lock().unwrap().try_iter().collect();
+        for message in messages {
+            match message {
+                PushMessage::Opened => {
+                    info!("Push channel opened");
+                    push.status = PushStatus::Open;
+                }
+                PushMessage::Update(update) => {
+                    room_update.send(RoomUpdateEvent(update));
+                }
+                PushMessage::Failed => {
+                    warn!("Push channel is unavailable, falling back to polling");
+                    push.status = PushStatus::Failed;
+                }
+            }
+        }
+    }
+}
@@ -8,42 +8,134 @@ impl Default for QueryTimer {
     }
 }
 
+#[derive(Debug, Clone, Event)]
+pub struct SyncEvent;
+
+#[derive(Debug, Clone, Event)]
+pub struct RoomUpdateEvent(pub RoomUpdate);
+
 pub(super) fn query_moves(
     mut request: EventWriter<TypedRequest<QueryResponse>>,
     connect: Res<Connection>,
+    push: Option<Res<PushChannel>>,
     time: Res<Time>,
     mut timer: Local<QueryTimer>,
 ) {
+    // Polling is only the fallback for when the server can't push to us
+    if push.is_some_and(|push| push.status != PushStatus::Failed) {
+        return;
+    }
     timer.0.tick(time.delta());
-    if timer.0.just_finished() {
-        if let Some(ref player) = connect.player {
-            //info!("Querying player moves");
-            let body = QueryRequest {
-                room: connect.room,
-                player: player.color.into(),
-            };
-            request.send(
-                HttpClient::new()
-                    .json(&body)
-                    .get(format!("{}/query", connect.url))
-                    .with_type(),
-            );
-        }
+    if timer.0.just_finished() && connect.player.is_some() {
+        //info!("Querying player moves");
+        let body = QueryRequest {
+            room: connect.room,
+            token: connect.token,
+        };
+        request.send(
+            HttpClient::new()
+                .json(&body)
+                .get(format!("{}/query", connect.url))
+                .with_type(),
+        );
     }
 }
 
 pub(super) fn respond_moves(
     mut response: EventReader<TypedResponse<QueryResponse>>,
-    mut update: EventWriter<UpdateEvent>,
-    mut board: ResMut<BoardInfo>,
+    mut room_update: EventWriter<RoomUpdateEvent>,
 ) {
     response.read().for_each(|response| {
-        if let Some(ref board_s) = response.board {
-            info!("Updating board to {:?}", board_s);
-            board.board = board_s.as_str().try_into().unwrap();
-            update.send(UpdateEvent);
+        if let Some(ref update) = response.update {
+            room_update.send(RoomUpdateEvent(update.clone()));
         } else {
             // Continue to wait
         }
     });
 }
+
+pub(super) fn apply_room_update(
+    mut room_update: EventReader<RoomUpdateEvent>,
+    mut update: EventWriter<UpdateEvent>,
+    mut sync: EventWriter<SyncEvent>,
+    mut board: ResMut<BoardInfo>,
+) {
+    room_update
+        .read()
+        .for_each(|room_update| match &room_update.0 {
+            RoomUpdate::Move(mv) => {
+                if mv.ply < board.ply {
+                    warn!("Ignoring duplicate move {:?}", mv);
+                    return;
+                }
+                if mv.ply > board.ply {
+                    warn!("Missed moves before {:?}, resyncing", mv);
+                    sync.send(SyncEvent);
+                    return;
+                }
+                match mv.iccs.parse::<Move>() {
+                    // Checked against the board itself, which is never stale
+                    Ok(parsed) if board.board.is_legal(parsed) => {
+                        info!("Playing opponent move {}", parsed);
+                        board.play(parsed);
+                        update.send(UpdateEvent);
+                    }
+                    _ => {
+                        warn!("Received illegal move {:?}, resyncing", mv);
+                        sync.send(SyncEvent);
+                    }
+                }
+            }
+            RoomUpdate::Resync(resync) => {
+                info!("Resyncing board to {:?}", resync);
+                board.resync(resync);
+                update.send(UpdateEvent);
+            }
+            RoomUpdate::Outcome(outcome) => {
+                info!("Game over: {}", outcome);
+                board.outcome = Some(*outcome);
+                update.send(UpdateEvent);
+            }
+            RoomUpdate::DrawOffered => {
+                info!("The opponent offers a draw, press D to accept");
+            }
+            RoomUpdate::Joined => {
+                info!("Opponent joined the room");
+            }
+            RoomUpdate::Closed => {
+                warn!("The room was closed by the server");
+            }
+        });
+}
+
+pub(super) fn listen_sync(
+    mut sync: EventReader<SyncEvent>,
+    mut request: EventWriter<TypedRequest<Resync>>,
+    connect: Res<Connection>,
+) {
+    // Several gaps found in one frame only need a single resync
+    if sync.read().count() > 0 && connect.player.is_some() {
+        let body = QueryRequest {
+            room: connect.room,
+            token: connect.token,
+        };
+        request.send(
+            HttpClient::new()
+                .json(&body)
+                .get(format!("{}/sync", connect.url))
+                .with_type(),
+        );
+    }
+}
+
+pub(super) fn respond_sync(
+    mut response: EventReader<TypedResponse<Resync>>,
+    mut update: EventWriter<UpdateEvent>,
+    mut board: ResMut<BoardInfo>,
+) {
+    response.read().for_each(|resync| {
+        info!("Resyncing board to {:?}", **resync);
+        board.resync(resync);
+        update.send(UpdateEvent);
+    });
+}
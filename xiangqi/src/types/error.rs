@@ -1,10 +0,0 @@
-#[derive(Debug)]
-pub struct Error;
-
-impl std::fmt::Display for Error {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Internal Error")
-    }
-}
-
-impl std::error::Error for Error {}
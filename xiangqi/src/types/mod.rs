@@ -1,8 +0,0 @@
-mod board;
-mod error;
-mod pieces;
-
-pub(super) use crate::prelude::*;
-pub use board::*;
-pub use error::*;
-pub use pieces::*;